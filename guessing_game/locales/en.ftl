@@ -0,0 +1,356 @@
+# English messages for the guessing game. This is the reference catalog: every other catalog
+# needs a translation for each message in here (see i18n.rs).
+# Variables are written as { $name }; counts select their plural form with [one] and *[other].
+
+## Shared
+
+interrupted = Interrupted, bye!
+bye = Bye!
+no-input = No more input. Bye!
+seed = Seed: { $seed }
+prompt = Please input your guess...
+prompt-attempts-left = Please input your guess... ({ $left ->
+        [one] { $left } attempt left
+       *[other] { $left } attempts left
+    })
+game-finished = the game is already finished
+prompt-time-left = Please input your guess... ({ $seconds }s left)
+prompt-attempts-and-time-left = Please input your guess... ({ $left ->
+        [one] { $left } attempt
+       *[other] { $left } attempts
+    } and { $seconds }s left)
+time-up = Time's up!
+
+## Command line
+
+config-failed = Problem parsing arguments: { $error }
+config-unknown-argument = unknown argument `{ $argument }`
+config-missing-value = `{ $flag }` needs a value
+config-invalid-value = invalid value `{ $value }` for `{ $flag }`
+config-empty-range = `--min` ({ $min }) can't be larger than `--max` ({ $max })
+config-at-least-one = `{ $flag }` must be at least 1
+config-no-time = `--time-limit` must be at least 1 second
+config-too-few-bots = a tournament needs at least two `--bot`s
+config-only-when-playing = `{ $flag }` only works when playing the plain game
+config-record-and-resume = `--record` can't be used with `--resume`
+config-secrets-unused = `--secrets` only works where a new secret is drawn: the plain game, tui, hot-cold, speedrun and batch
+config-unsupported-language = unsupported language `{ $lang }` (expected { $supported })
+config-dates-reversed = `--from` ({ $from }) can't be after `--to` ({ $to })
+secrets-failed = Problem setting up the secrets: { $error }
+secrets-unknown = unknown secret source `{ $name }` (expected uniform, edges, middle, file:<path> or phrase:<text>)
+secrets-unreadable = could not read { $path }: { $error }
+secrets-not-a-number = { $path } line { $line }: `{ $text }` is not a whole number
+secrets-empty = { $path } has no numbers
+secrets-out-of-range = { $secret } in { $source } is not between { $min } and { $max }
+batch-failed = Problem reading the guesses: { $error }
+tui-failed = Problem running the terminal UI: { $error }
+
+## Playing the number game
+
+guess-the-number = Guess the number!
+difficulty-range = Difficulty: { $difficulty }, the secret is between { $min } and { $max }.
+evil-host = Beware: the host is evil, it won't decide on a secret until it has to.
+commands = Type :hint for a hint, :save to save the game, or :quit to save it and stop.
+no-input-secret = No more input, the secret number was { $secret }. Bye!
+unknown-command = Unknown command `:{ $command }`, try :hint, :save or :quit
+you-guessed = You guessed: { $guess }
+too-small = Too small!
+too-big = Too big!
+you-win = You win!
+found-it = Found it in { $attempts ->
+        [one] { $attempts } attempt
+       *[other] { $attempts } attempts
+    } and { $seconds } seconds. Score: { $score }
+you-lose = You lose! No attempts left after { $attempts ->
+        [one] { $attempts } guess
+       *[other] { $attempts } guesses
+    }.
+secret-was = The secret number was { $secret }.
+guess-time-up = the time is up
+leaderboard-failed = Could not save to the leaderboard: { $error }
+leaderboard-empty = No games on the leaderboard yet.
+leaderboard-read-failed = Problem reading the leaderboard: { $error }
+leaderboard-name = Name
+leaderboard-difficulty = Difficulty
+leaderboard-attempts = Attempts
+leaderboard-time = Time
+leaderboard-score = Score
+leaderboard-date = Date
+stats-failed = Could not save the statistics: { $error }
+stats-read-failed = Problem reading the statistics: { $error }
+resumed = Resumed { $file }: { $attempts ->
+        [one] { $attempts } guess
+       *[other] { $attempts } guesses
+    } so far, { $seconds } seconds on the clock.
+resume-failed = Could not resume { $file }: { $error }
+saved = Saved to { $file }, continue with --resume { $file }
+save-failed = Could not save the game: { $error }
+save-invalid = not a valid save file: { $error }
+save-unknown-version = unknown version { $version }
+save-tampered = the save file was modified, it can't be resumed
+replayed = { $file }: { $inputs ->
+        [one] the input was
+       *[other] all { $inputs } inputs were
+    } replayed with the same answers
+replay-failed = Could not replay { $file }: { $error }
+transcript-invalid = not a valid transcript, line { $line }: { $error }
+transcript-setup = can't set up the game again: { $error }
+transcript-mismatch = line { $line }: expected `{ $expected }`, got `{ $got }`
+transcript-record-failed = Problem recording the transcript: { $error }
+transcript-empty = empty file
+transcript-no-magic = expected `{ $magic }`
+transcript-unknown-version = unknown version { $version }
+transcript-invalid-value = invalid { $setting } `{ $value }`
+transcript-unknown-event = unknown event `{ $event }`
+transcript-after-events = `{ $setting }` after the first event
+transcript-unknown-setting = unknown setting `{ $setting }`
+transcript-missing = no `{ $setting }`
+transcript-no-response = no response
+transcript-no-input = no input
+
+## Input
+
+input-eof = no more input
+input-empty = please type a number
+input-not-a-number = `{ $text }` is not a whole number
+input-negative = { $guess } is negative, but the secret never is
+input-overflow = { $text } is too large to be a guess
+input-io = failed to read input: { $error }
+input-timed-out = no input in time
+out-of-range = { $guess } is out of range, the secret is between { $min } and { $max }
+
+## Hints
+
+hints-list = Hints cost a part of your score:
+hint-given = Hint: { $clue } (-{ $cost }% score)
+no-hint = No hint: { $error }
+clue-even = the secret is even
+clue-odd = the secret is odd
+clue-divisible = the secret is divisible by { $divisor }
+clue-not-divisible = the secret is not divisible by { $divisor }
+clue-between = the secret is between { $low } and { $high }
+clue-within = the secret is within { $band } of { $guess }
+clue-further = the secret is more than { $band } away from { $guess }
+hint-unknown = there is no `{ $name }` hint
+hint-not-applicable = the `{ $name }` hint doesn't apply yet
+hint-no-secret = the evil host doesn't give hints
+hint-contradiction = the `{ $name }` hint came up with a wrong clue ({ $clue })
+
+## Statistics
+
+stats-title = Statistics for { $name }:
+stats-empty = No games played by { $name } yet.
+stats-games = Games played
+stats-wins = Games won
+stats-win-rate = Win rate
+stats-mean = Mean attempts
+stats-median = Median attempts
+stats-best-streak = Best streak
+stats-histogram = Attempts per won game:
+
+## Speed run
+
+speedrun-intro = Speed run: { $rounds ->
+        [one] { $rounds } round
+       *[other] { $rounds } rounds
+    } with secrets between { $min } and { $max }. The clock starts now!
+speedrun-round = Round { $round } of { $rounds }
+speedrun-split = Split: { $time }s, { $total }s in total.
+speedrun-total = Total time: { $total }s, won { $won } of { $rounds }.
+split-round = Round
+split-attempts = Attempts
+split-time = Split
+split-total = Total
+split-result = Result
+split-won = won
+split-lost = lost
+
+## Bulls and cows
+
+code-intro = Guess the code: { $length } different symbols out of { $alphabet }.
+code-rules = Bulls are right symbols in the right place, cows right symbols in the wrong place.
+code-no-input = No more input, the code was { $code }. Bye!
+code-won = You win! Cracked the code in { $attempts ->
+        [one] { $attempts } attempt
+       *[other] { $attempts } attempts
+    }.
+code-was = The code was { $code }.
+feedback = { $bulls ->
+        [one] { $bulls } bull
+       *[other] { $bulls } bulls
+    }, { $cows ->
+        [one] { $cows } cow
+       *[other] { $cows } cows
+    }
+code-wrong-length = the code has { $expected } symbols, your guess has { $got }
+code-not-in-alphabet = `{ $symbol }` can't be part of the code
+code-repeated = `{ $symbol }` is used more than once, the symbols are all different
+code-setup-failed = Problem setting up the game: { $error }
+code-invalid-secret = invalid secret: { $error }
+code-too-long = a code of { $length } distinct symbols can't be made from { $symbols } symbols
+
+## Hot and cold
+
+hot-intro = Find the secret number between { $min } and { $max }. There's no higher or lower, only warmer or colder.
+hot-intro-grid = Find the secret point on the grid from { $min } to { $max } on both axes, typed as x,y. There's no higher or lower, only warmer or colder.
+hot-rules = Every guess is compared with the one before it: warmer is closer to the secret, colder further away.
+hot-no-input = No more input, the secret was { $secret }. Bye!
+hot-first = That's where you start. Guess again to find out if you're getting warmer.
+hot-warmer = Warmer!
+hot-colder = Colder!
+hot-same = Neither warmer nor colder.
+hot-found = Found it!
+hot-won = You win! Found the secret in { $attempts ->
+        [one] { $attempts } attempt
+       *[other] { $attempts } attempts
+    }.
+hot-secret-was = The secret was { $secret }, your last guess was { $distance } away.
+hot-coordinates = type { $expected ->
+        [one] one number
+       *[other] { $expected } numbers, like 3,4
+    }, not { $got }
+
+## Reverse mode
+
+reverse-intro = Think of a number between { $min } and { $max }, I'll guess it!
+reverse-rules = Answer every guess with h (higher), l (lower) or c (correct).
+reverse-ask = Is it { $guess }?
+reverse-bad-answer = Please answer h, l or c.
+reverse-conflict = That can't be right, { $conflict }.
+reverse-found = Got it, your number is { $number }! That took me { $guesses ->
+        [one] { $guesses } guess
+       *[other] { $guesses } guesses
+    }.
+answer = answer { $turn } ({ $guess }: { $answer })
+answer-higher = higher
+answer-lower = lower
+answer-correct = correct
+conflict-answers = { $higher } conflicts with { $lower }: your number would have to be above { $above } and below { $below }
+conflict-below-range = { $lower } conflicts with the range: nothing is below { $min }
+conflict-above-range = { $higher } conflicts with the range: nothing is above { $max }
+
+## Bots
+
+bot-unknown-strategy = Unknown strategy `{ $name }` (expected binary, random, linear or biased)
+bot-intro = { $games ->
+        [one] { $games } game
+       *[other] { $games } games
+    } per strategy against the { $host } host, secrets between { $min } and { $max }, seed { $seed }
+bot-invalid-guess = Strategy `{ $name }` made an invalid guess: { $error }
+bot-report = { $name }: mean { $mean } attempts, best { $best }, worst { $worst }
+plugin-invalid = not a valid bot: { $error }
+plugin-out-of-fuel = ran out of fuel
+plugin-out-of-memory = used more memory than allowed
+plugin-trap = crashed: { $error }
+plugin-missing-export = no `{ $name }` export
+plugin-wrong-version = written for version { $version } of the interface, this is version { $expected }
+plugin-random-reversed = random({ $low }, { $high }): low is larger than high
+tournament-intro = Round robin between { $bots } bots, { $games ->
+        [one] { $games } game
+       *[other] { $games } games
+    } per match against the { $host } host, secrets between { $min } and { $max }, seed { $seed }
+bot-load-failed = Problem loading the bot { $file }: { $error }
+tournament-rank = Rank
+tournament-bot = Bot
+tournament-mean = Mean
+tournament-won = Won
+tournament-drawn = Drawn
+tournament-lost = Lost
+tournament-disqualified = disqualified: { $reason }
+tournament-guessed = guessed { $guess }: { $error }
+
+## Multiplayer
+
+join-intro = Type a number to guess, or `quit` to leave.
+server-closed = The server closed the connection.
+connection-lost = Lost the connection: { $error }
+server-welcome = Connected! Round { $round }: guess a number between { $min } and { $max }.
+server-too-small = { $guess }: Too small!
+server-too-big = { $guess }: Too big!
+server-correct = { $guess }: You win this round!
+server-winner = { $name } found the secret in { $attempts ->
+        [one] { $attempts } attempt
+       *[other] { $attempts } attempts
+    }.
+server-round = Round { $round } starts: a new number between { $min } and { $max }.
+server-joined = { $name } joined.
+server-left = { $name } left.
+server-error = Server says: { $error }
+listen-failed = Could not listen on { $addr }: { $error }
+connect-failed = Could not connect to { $addr }: { $error }
+server-stopped = Server stopped: { $error }
+serve-hosting = Hosting rounds between { $min } and { $max } on { $addr }, seed { $seed }
+http-serving = Serving the HTTP API on http://{ $addr }
+
+## Terminal UI
+
+tui-title = Guess the number!
+tui-subtitle = { $difficulty }: { $min } to { $max }, { $host } host, seed { $seed }
+tui-start = Type a number and press Enter.
+tui-too-small = { $guess } is too small!
+tui-too-big = { $guess } is too big!
+tui-right = { $guess } is right!
+tui-won = You win! Found { $guess } in { $attempts ->
+        [one] { $attempts } attempt
+       *[other] { $attempts } attempts
+    }, score { $score }. Press any key.
+tui-lost = You lose! The secret number was { $secret }. Press any key.
+tui-still-possible = Still possible: { $low } to { $high }
+tui-guesses = Guesses
+tui-answer-too-small = too small
+tui-answer-too-big = too big
+tui-answer-correct = correct
+tui-game = Game
+tui-attempts = Attempts: { $attempts }
+tui-attempts-of = Attempts: { $attempts } of { $max }
+tui-time = Time: { $seconds }s
+tui-time-left = Time left: { $seconds }s
+tui-time-up = Time's up! The secret number was { $secret }. Press any key.
+tui-your-guess = Your guess
+tui-keys = Enter: guess  Backspace: delete  Esc: quit
+tui-summary-won = You won in { $attempts ->
+        [one] { $attempts } attempt
+       *[other] { $attempts } attempts
+    } and { $seconds } seconds. Score: { $score }
+tui-summary-lost = You lost after { $attempts ->
+        [one] { $attempts } attempt
+       *[other] { $attempts } attempts
+    }, the secret number was { $secret }.
+tui-summary-quit = Quit after { $attempts ->
+        [one] { $attempts } attempt
+       *[other] { $attempts } attempts
+    }, the secret number was { $secret }.
+
+## Achievements
+
+achievement-unlocked = Achievement unlocked: { $name }! ({ $description })
+achievements-failed = Could not save the achievements: { $error }
+achievements-read-failed = Problem reading the achievements: { $error }
+achievements-title = Achievements of { $name } ({ $unlocked } of { $total }):
+achievement-locked = locked
+achievement-unlocked-on = unlocked on { $date }
+achievement-first-guess = Bullseye
+achievement-first-guess-description = win with your very first guess
+achievement-binary-search = By the book
+achievement-binary-search-description = win with every guess right in the middle of what's left
+achievement-streak = On a roll
+achievement-streak-description = win { $streak } games in a row
+achievement-hard = Hard as nails
+achievement-hard-description = win on hard difficulty
+
+## Ratings
+
+rating-changed = Your rating is now { $rating } ({ $difference }), against { $players ->
+        [one] the other player
+       *[other] { $players } other players
+    } of this round.
+ratings-failed = Could not save the ratings: { $error }
+ratings-read-failed = Problem reading the ratings: { $error }
+ratings-empty = Nobody has a rating yet: play a round with the same --seed as somebody else.
+ratings-unknown = { $name } has no rating yet.
+ratings-history = Rating of { $name }: { $rating } ± { $deviation }
+ratings-tournament = { $name }: rating { $before } -> { $after } ({ $difference })
+ratings-name = Name
+ratings-rating = Rating
+ratings-deviation = RD
+ratings-games = Games
@@ -0,0 +1,354 @@
+# Nederlandse berichten voor het raadspel. Elk bericht uit en.ftl heeft hier een vertaling nodig.
+
+## Gedeeld
+
+interrupted = Onderbroken, doei!
+bye = Doei!
+no-input = Geen invoer meer. Doei!
+seed = Seed: { $seed }
+prompt = Voer je gok in...
+prompt-attempts-left = Voer je gok in... (nog { $left ->
+        [one] { $left } poging
+       *[other] { $left } pogingen
+    })
+game-finished = het spel is al afgelopen
+prompt-time-left = Voer je gok in... (nog { $seconds }s)
+prompt-attempts-and-time-left = Voer je gok in... (nog { $left ->
+        [one] { $left } poging
+       *[other] { $left } pogingen
+    } en { $seconds }s)
+time-up = De tijd is op!
+
+## Opdrachtregel
+
+config-failed = Probleem met de argumenten: { $error }
+config-unknown-argument = onbekend argument `{ $argument }`
+config-missing-value = `{ $flag }` heeft een waarde nodig
+config-invalid-value = ongeldige waarde `{ $value }` voor `{ $flag }`
+config-empty-range = `--min` ({ $min }) kan niet groter zijn dan `--max` ({ $max })
+config-at-least-one = `{ $flag }` moet minstens 1 zijn
+config-no-time = `--time-limit` moet minstens 1 seconde zijn
+config-too-few-bots = een toernooi heeft minstens twee keer `--bot` nodig
+config-only-when-playing = `{ $flag }` werkt alleen bij het gewone spel
+config-record-and-resume = `--record` kan niet samen met `--resume`
+config-secrets-unused = `--secrets` werkt alleen waar een nieuw geheim getrokken wordt: het gewone spel, tui, hot-cold, speedrun en batch
+config-unsupported-language = onbekende taal `{ $lang }` (verwacht { $supported })
+config-dates-reversed = `--from` ({ $from }) kan niet na `--to` ({ $to }) liggen
+secrets-failed = Probleem bij het instellen van de geheimen: { $error }
+secrets-unknown = onbekende bron voor geheimen `{ $name }` (verwacht uniform, edges, middle, file:<pad> of phrase:<tekst>)
+secrets-unreadable = kon { $path } niet lezen: { $error }
+secrets-not-a-number = { $path } regel { $line }: `{ $text }` is geen geheel getal
+secrets-empty = { $path } bevat geen getallen
+secrets-out-of-range = { $secret } in { $source } ligt niet tussen { $min } en { $max }
+batch-failed = Probleem bij het lezen van de gokken: { $error }
+tui-failed = Probleem met de terminal-UI: { $error }
+
+## Het getallenspel
+
+guess-the-number = Raad het getal!
+difficulty-range = Moeilijkheid: { $difficulty }, het geheime getal ligt tussen { $min } en { $max }.
+evil-host = Pas op: de gastheer is gemeen, hij kiest pas een getal als het echt moet.
+commands = Typ :hint voor een hint, :save om het spel op te slaan, of :quit om op te slaan en te stoppen.
+no-input-secret = Geen invoer meer, het geheime getal was { $secret }. Doei!
+unknown-command = Onbekend commando `:{ $command }`, probeer :hint, :save of :quit
+you-guessed = Je gokte: { $guess }
+too-small = Te klein!
+too-big = Te groot!
+you-win = Je wint!
+found-it = Gevonden in { $attempts ->
+        [one] { $attempts } poging
+       *[other] { $attempts } pogingen
+    } en { $seconds } seconden. Score: { $score }
+you-lose = Je verliest! Geen pogingen meer na { $attempts ->
+        [one] { $attempts } gok
+       *[other] { $attempts } gokken
+    }.
+secret-was = Het geheime getal was { $secret }.
+guess-time-up = de tijd is op
+leaderboard-failed = Kon niet opslaan in de ranglijst: { $error }
+leaderboard-empty = Nog geen spellen in de ranglijst.
+leaderboard-read-failed = Probleem bij het lezen van het scorebord: { $error }
+leaderboard-name = Naam
+leaderboard-difficulty = Niveau
+leaderboard-attempts = Pogingen
+leaderboard-time = Tijd
+leaderboard-score = Score
+leaderboard-date = Datum
+stats-failed = Kon de statistieken niet opslaan: { $error }
+stats-read-failed = Probleem bij het lezen van de statistieken: { $error }
+resumed = { $file } hervat: tot nu toe { $attempts ->
+        [one] { $attempts } gok
+       *[other] { $attempts } gokken
+    }, { $seconds } seconden op de klok.
+resume-failed = Kon { $file } niet hervatten: { $error }
+saved = Opgeslagen in { $file }, ga verder met --resume { $file }
+save-failed = Kon het spel niet opslaan: { $error }
+save-invalid = geen geldig opgeslagen spel: { $error }
+save-unknown-version = onbekende versie { $version }
+save-tampered = het opgeslagen spel is aangepast, het kan niet verder gespeeld worden
+replayed = { $file }: { $inputs ->
+        [one] de invoer is
+       *[other] alle { $inputs } invoeren zijn
+    } opnieuw gespeeld met dezelfde antwoorden
+replay-failed = Kon { $file } niet opnieuw spelen: { $error }
+transcript-invalid = geen geldig transcript, regel { $line }: { $error }
+transcript-setup = kan het spel niet opnieuw opzetten: { $error }
+transcript-mismatch = regel { $line }: verwacht `{ $expected }`, maar kreeg `{ $got }`
+transcript-record-failed = Probleem bij het opnemen van het transcript: { $error }
+transcript-empty = leeg bestand
+transcript-no-magic = verwacht `{ $magic }`
+transcript-unknown-version = onbekende versie { $version }
+transcript-invalid-value = ongeldige { $setting } `{ $value }`
+transcript-unknown-event = onbekende gebeurtenis `{ $event }`
+transcript-after-events = `{ $setting }` na de eerste gebeurtenis
+transcript-unknown-setting = onbekende instelling `{ $setting }`
+transcript-missing = geen `{ $setting }`
+transcript-no-response = geen antwoord
+transcript-no-input = geen invoer
+
+## Invoer
+
+input-eof = geen invoer meer
+input-empty = typ een getal
+input-not-a-number = `{ $text }` is geen geheel getal
+input-negative = { $guess } is negatief, maar het geheime getal nooit
+input-overflow = { $text } is te groot voor een gok
+input-io = invoer lezen mislukt: { $error }
+input-timed-out = geen invoer op tijd
+out-of-range = { $guess } valt buiten het bereik, het geheime getal ligt tussen { $min } en { $max }
+
+## Hints
+
+hints-list = Hints kosten een deel van je score:
+hint-given = Hint: { $clue } (-{ $cost }% score)
+no-hint = Geen hint: { $error }
+clue-even = het geheime getal is even
+clue-odd = het geheime getal is oneven
+clue-divisible = het geheime getal is deelbaar door { $divisor }
+clue-not-divisible = het geheime getal is niet deelbaar door { $divisor }
+clue-between = het geheime getal ligt tussen { $low } en { $high }
+clue-within = het geheime getal ligt hoogstens { $band } van { $guess } af
+clue-further = het geheime getal ligt meer dan { $band } van { $guess } af
+hint-unknown = er is geen hint `{ $name }`
+hint-not-applicable = de hint `{ $name }` kan nog niet
+hint-no-secret = de gemene gastheer geeft geen hints
+hint-contradiction = de hint `{ $name }` gaf een verkeerde aanwijzing ({ $clue })
+
+## Statistieken
+
+stats-title = Statistieken van { $name }:
+stats-empty = { $name } heeft nog geen spellen gespeeld.
+stats-games = Gespeeld
+stats-wins = Gewonnen
+stats-win-rate = Winstpercentage
+stats-mean = Gemiddeld aantal pogingen
+stats-median = Mediaan pogingen
+stats-best-streak = Langste reeks
+stats-histogram = Pogingen per gewonnen spel:
+
+## Speedrun
+
+speedrun-intro = Speedrun: { $rounds ->
+        [one] { $rounds } ronde
+       *[other] { $rounds } rondes
+    } met geheime getallen tussen { $min } en { $max }. De klok loopt!
+speedrun-round = Ronde { $round } van { $rounds }
+speedrun-split = Tussentijd: { $time }s, { $total }s in totaal.
+speedrun-total = Totale tijd: { $total }s, { $won } van de { $rounds } gewonnen.
+split-round = Ronde
+split-attempts = Pogingen
+split-time = Tijd
+split-total = Totaal
+split-result = Uitslag
+split-won = gewonnen
+split-lost = verloren
+
+## Bulls and cows
+
+code-intro = Raad de code: { $length } verschillende tekens uit { $alphabet }.
+code-rules = Bulls zijn goede tekens op de goede plek, cows goede tekens op de verkeerde plek.
+code-no-input = Geen invoer meer, de code was { $code }. Doei!
+code-won = Je wint! De code gekraakt in { $attempts ->
+        [one] { $attempts } poging
+       *[other] { $attempts } pogingen
+    }.
+code-was = De code was { $code }.
+feedback = { $bulls ->
+        [one] { $bulls } bull
+       *[other] { $bulls } bulls
+    }, { $cows ->
+        [one] { $cows } cow
+       *[other] { $cows } cows
+    }
+code-wrong-length = de code heeft { $expected } tekens, je gok heeft er { $got }
+code-not-in-alphabet = `{ $symbol }` kan niet in de code zitten
+code-repeated = `{ $symbol }` komt vaker dan eens voor, de tekens zijn allemaal verschillend
+code-setup-failed = Probleem bij het opzetten van het spel: { $error }
+code-invalid-secret = ongeldige code: { $error }
+code-too-long = een code van { $length } verschillende symbolen kan niet gemaakt worden uit { $symbols } symbolen
+
+## Warm en koud
+
+hot-intro = Vind het geheime getal tussen { $min } en { $max }. Er is geen hoger of lager, alleen warmer of kouder.
+hot-intro-grid = Vind het geheime punt op het rooster van { $min } tot { $max } op beide assen, getypt als x,y. Er is geen hoger of lager, alleen warmer of kouder.
+hot-rules = Elke gok wordt vergeleken met de vorige: warmer is dichter bij het geheim, kouder verder weg.
+hot-no-input = Geen invoer meer, het geheim was { $secret }. Doei!
+hot-first = Daar begin je. Gok nog eens om te horen of je warmer wordt.
+hot-warmer = Warmer!
+hot-colder = Kouder!
+hot-same = Niet warmer en niet kouder.
+hot-found = Gevonden!
+hot-won = Je wint! Het geheim gevonden in { $attempts ->
+        [one] { $attempts } poging
+       *[other] { $attempts } pogingen
+    }.
+hot-secret-was = Het geheim was { $secret }, je laatste gok zat er { $distance } vanaf.
+hot-coordinates = typ { $expected ->
+        [one] één getal
+       *[other] { $expected } getallen, zoals 3,4
+    }, niet { $got }
+
+## Omgekeerd spel
+
+reverse-intro = Denk aan een getal tussen { $min } en { $max }, ik raad het!
+reverse-rules = Beantwoord elke gok met h (hoger), l (lager) of c (correct).
+reverse-ask = Is het { $guess }?
+reverse-bad-answer = Antwoord met h, l of c.
+reverse-conflict = Dat kan niet kloppen, { $conflict }.
+reverse-found = Hebbes, je getal is { $number }! Daar had ik { $guesses ->
+        [one] { $guesses } gok
+       *[other] { $guesses } gokken
+    } voor nodig.
+answer = antwoord { $turn } ({ $guess }: { $answer })
+answer-higher = hoger
+answer-lower = lager
+answer-correct = correct
+conflict-answers = { $higher } botst met { $lower }: je getal zou boven { $above } en onder { $below } moeten liggen
+conflict-below-range = { $lower } botst met het bereik: er is niets onder { $min }
+conflict-above-range = { $higher } botst met het bereik: er is niets boven { $max }
+
+## Bots
+
+bot-unknown-strategy = Onbekende strategie `{ $name }` (verwacht binary, random, linear of biased)
+bot-intro = { $games ->
+        [one] { $games } spel
+       *[other] { $games } spellen
+    } per strategie tegen de { $host } host, geheimen tussen { $min } en { $max }, seed { $seed }
+bot-invalid-guess = Strategie `{ $name }` deed een ongeldige gok: { $error }
+bot-report = { $name }: gemiddeld { $mean } pogingen, beste { $best }, slechtste { $worst }
+plugin-invalid = geen geldige bot: { $error }
+plugin-out-of-fuel = de brandstof is op
+plugin-out-of-memory = gebruikte meer geheugen dan toegestaan
+plugin-trap = gecrasht: { $error }
+plugin-missing-export = geen export `{ $name }`
+plugin-wrong-version = geschreven voor versie { $version } van de interface, dit is versie { $expected }
+plugin-random-reversed = random({ $low }, { $high }): low is groter dan high
+tournament-intro = Competitie tussen { $bots } bots, { $games ->
+        [one] { $games } spel
+       *[other] { $games } spellen
+    } per wedstrijd tegen de { $host } host, geheimen tussen { $min } en { $max }, seed { $seed }
+bot-load-failed = Probleem bij het laden van de bot { $file }: { $error }
+tournament-rank = Plaats
+tournament-bot = Bot
+tournament-mean = Gemiddeld
+tournament-won = Gewonnen
+tournament-drawn = Gelijk
+tournament-lost = Verloren
+tournament-disqualified = gediskwalificeerd: { $reason }
+tournament-guessed = gokte { $guess }: { $error }
+
+## Multiplayer
+
+join-intro = Typ een getal om te gokken, of `quit` om te vertrekken.
+server-closed = De server heeft de verbinding gesloten.
+connection-lost = Verbinding verloren: { $error }
+server-welcome = Verbonden! Ronde { $round }: raad een getal tussen { $min } en { $max }.
+server-too-small = { $guess }: Te klein!
+server-too-big = { $guess }: Te groot!
+server-correct = { $guess }: Je wint deze ronde!
+server-winner = { $name } vond het geheime getal in { $attempts ->
+        [one] { $attempts } poging
+       *[other] { $attempts } pogingen
+    }.
+server-round = Ronde { $round } begint: een nieuw getal tussen { $min } en { $max }.
+server-joined = { $name } doet mee.
+server-left = { $name } is vertrokken.
+server-error = De server zegt: { $error }
+listen-failed = Kon niet luisteren op { $addr }: { $error }
+connect-failed = Kon geen verbinding maken met { $addr }: { $error }
+server-stopped = Server gestopt: { $error }
+serve-hosting = Rondes met getallen tussen { $min } en { $max } op { $addr }, seed { $seed }
+http-serving = De HTTP-API draait op http://{ $addr }
+
+## Terminal-UI
+
+tui-title = Raad het getal!
+tui-subtitle = { $difficulty }: { $min } tot { $max }, { $host } host, seed { $seed }
+tui-start = Typ een getal en druk op Enter.
+tui-too-small = { $guess } is te klein!
+tui-too-big = { $guess } is te groot!
+tui-right = { $guess } is goed!
+tui-won = Je wint! { $guess } gevonden in { $attempts ->
+        [one] { $attempts } poging
+       *[other] { $attempts } pogingen
+    }, score { $score }. Druk op een toets.
+tui-lost = Je verliest! Het geheime getal was { $secret }. Druk op een toets.
+tui-still-possible = Nog mogelijk: { $low } tot { $high }
+tui-guesses = Gokken
+tui-answer-too-small = te klein
+tui-answer-too-big = te groot
+tui-answer-correct = goed
+tui-game = Spel
+tui-attempts = Pogingen: { $attempts }
+tui-attempts-of = Pogingen: { $attempts } van { $max }
+tui-time = Tijd: { $seconds }s
+tui-time-left = Tijd over: { $seconds }s
+tui-time-up = De tijd is op! Het geheime getal was { $secret }. Druk op een toets.
+tui-your-guess = Je gok
+tui-keys = Enter: gokken  Backspace: wissen  Esc: stoppen
+tui-summary-won = Je won in { $attempts ->
+        [one] { $attempts } poging
+       *[other] { $attempts } pogingen
+    } en { $seconds } seconden. Score: { $score }
+tui-summary-lost = Je verloor na { $attempts ->
+        [one] { $attempts } poging
+       *[other] { $attempts } pogingen
+    }, het geheime getal was { $secret }.
+tui-summary-quit = Gestopt na { $attempts ->
+        [one] { $attempts } poging
+       *[other] { $attempts } pogingen
+    }, het geheime getal was { $secret }.
+
+## Prestaties
+
+achievement-unlocked = Prestatie behaald: { $name }! ({ $description })
+achievements-failed = Kon de prestaties niet opslaan: { $error }
+achievements-read-failed = Probleem bij het lezen van de prestaties: { $error }
+achievements-title = Prestaties van { $name } ({ $unlocked } van { $total }):
+achievement-locked = nog niet behaald
+achievement-unlocked-on = behaald op { $date }
+achievement-first-guess = Roos
+achievement-first-guess-description = win met je allereerste gok
+achievement-binary-search = Volgens het boekje
+achievement-binary-search-description = win met elke gok precies in het midden van wat er over is
+achievement-streak = Op dreef
+achievement-streak-description = win { $streak } spellen op rij
+achievement-hard = Keihard
+achievement-hard-description = win op moeilijk
+
+## Ratings
+
+rating-changed = Je rating is nu { $rating } ({ $difference }), tegen { $players ->
+        [one] de andere speler
+       *[other] de { $players } andere spelers
+    } van deze ronde.
+ratings-failed = Kon de ratings niet opslaan: { $error }
+ratings-read-failed = Probleem bij het lezen van de ratings: { $error }
+ratings-empty = Nog niemand heeft een rating: speel een ronde met dezelfde --seed als iemand anders.
+ratings-unknown = { $name } heeft nog geen rating.
+ratings-history = Rating van { $name }: { $rating } ± { $deviation }
+ratings-tournament = { $name }: rating { $before } -> { $after } ({ $difference })
+ratings-name = Naam
+ratings-rating = Rating
+ratings-deviation = RD
+ratings-games = Partijen
@@ -0,0 +1,448 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::bot::Bounds;
+use crate::difficulty::Difficulty;
+use crate::game::{Game, Outcome};
+use crate::{i18n, storage};
+
+// ========== Achievements ==========
+/*
+Achievements are unlocked once per player and kept for good, in `achievements.json` in the data
+dir (with the same locking as the leaderboard, see storage.rs).
+
+A finished game is turned into a stream of events: one per guess, and one for how it ended.
+Every achievement is a condition on one event, plus what's known from the events before it:
+- `first-guess`: won with the very first guess.
+- `binary-search`: won with every guess in the middle of what was still possible, the way binary
+  search plays. With an even count left either of the two middle numbers counts.
+- `streak`: won `STREAK` games in a row. The streak goes on from one session to the next, and a
+  lost game ends it; a game that was never finished doesn't count either way.
+- `hard`: won on hard difficulty.
+ */
+pub const FILE_NAME: &str = "achievements.json";
+pub const STREAK: u32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    // A guess, with the bounds the secret was known to be within before it.
+    Guess {
+        guess: i64,
+        ordering: Ordering,
+        bounds: Bounds,
+    },
+    Won {
+        attempts: u32,
+        difficulty: Difficulty,
+    },
+    Lost {
+        attempts: u32,
+    },
+}
+
+// The events of a game, in the order they happened. Empty while it's still running.
+pub fn events(game: &Game, difficulty: Difficulty) -> Vec<Event> {
+    let end = match game.outcome() {
+        Outcome::InProgress => return Vec::new(),
+        Outcome::Won { attempts } => Event::Won {
+            attempts,
+            difficulty,
+        },
+        Outcome::Lost { attempts } => Event::Lost { attempts },
+    };
+
+    let mut bounds = Bounds::new(game.range());
+    let mut events = Vec::new();
+    for &(guess, ordering) in game.history() {
+        events.push(Event::Guess {
+            guess,
+            ordering,
+            bounds,
+        });
+        bounds.update(guess, ordering);
+    }
+    events.push(end);
+    events
+}
+
+// What a condition knows besides the event itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct State {
+    // Games won in a row, including this one once it's won.
+    pub streak: u32,
+    // Whether every guess of this game so far was in the middle of the bounds.
+    pub halved: bool,
+}
+
+pub trait Achievement {
+    // Also the start of its message ids in the catalogs, e.g. `achievement-first-guess`.
+    fn id(&self) -> &'static str;
+
+    fn unlocks(&self, event: &Event, state: &State) -> bool;
+}
+
+pub struct FirstGuess;
+
+impl Achievement for FirstGuess {
+    fn id(&self) -> &'static str {
+        "first-guess"
+    }
+
+    fn unlocks(&self, event: &Event, _state: &State) -> bool {
+        matches!(event, Event::Won { attempts: 1, .. })
+    }
+}
+
+pub struct BinarySearch;
+
+impl Achievement for BinarySearch {
+    fn id(&self) -> &'static str {
+        "binary-search"
+    }
+
+    fn unlocks(&self, event: &Event, state: &State) -> bool {
+        matches!(event, Event::Won { .. }) && state.halved
+    }
+}
+
+pub struct Streak;
+
+impl Achievement for Streak {
+    fn id(&self) -> &'static str {
+        "streak"
+    }
+
+    fn unlocks(&self, event: &Event, state: &State) -> bool {
+        matches!(event, Event::Won { .. }) && state.streak >= STREAK
+    }
+}
+
+pub struct Hard;
+
+impl Achievement for Hard {
+    fn id(&self) -> &'static str {
+        "hard"
+    }
+
+    fn unlocks(&self, event: &Event, _state: &State) -> bool {
+        matches!(
+            event,
+            Event::Won {
+                difficulty: Difficulty::Hard,
+                ..
+            }
+        )
+    }
+}
+
+pub fn achievements() -> Vec<Box<dyn Achievement>> {
+    vec![
+        Box::new(FirstGuess),
+        Box::new(BinarySearch),
+        Box::new(Streak),
+        Box::new(Hard),
+    ]
+}
+
+// In the middle: as many numbers left below the guess as above it, give or take one.
+// In i128, the bounds can span all of i64.
+fn halves(bounds: &Bounds, guess: i64) -> bool {
+    let below = guess as i128 - bounds.low as i128;
+    let above = bounds.high as i128 - guess as i128;
+    below >= 0 && above >= 0 && (below - above).abs() <= 1
+}
+
+// The name of an achievement, in the player's language.
+pub fn name(id: &str) -> String {
+    i18n::text(&format!("achievement-{id}"))
+}
+
+// What it takes to unlock it.
+pub fn description(id: &str) -> String {
+    i18n::message(
+        &format!("achievement-{id}-description"),
+        &[("streak", &STREAK)],
+    )
+}
+
+// ========== Progress ==========
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Progress {
+    // The ids of the unlocked achievements, with when they were unlocked.
+    pub unlocked: BTreeMap<String, DateTime<Utc>>,
+    pub streak: u32,
+}
+
+impl Progress {
+    // Runs the events through every achievement, and returns the ids of the ones they unlocked.
+    pub fn apply(&mut self, events: &[Event]) -> Vec<&'static str> {
+        let mut state = State {
+            streak: self.streak,
+            halved: true,
+        };
+        let mut unlocked = Vec::new();
+        for event in events {
+            match event {
+                Event::Guess { guess, bounds, .. } => state.halved &= halves(bounds, *guess),
+                Event::Won { .. } => state.streak += 1,
+                Event::Lost { .. } => state.streak = 0,
+            }
+
+            for achievement in achievements() {
+                let id = achievement.id();
+                if !self.unlocked.contains_key(id) && achievement.unlocks(event, &state) {
+                    self.unlocked.insert(id.to_string(), Utc::now());
+                    unlocked.push(id);
+                }
+            }
+
+            // The next game starts with a clean slate.
+            if !matches!(event, Event::Guess { .. }) {
+                state.halved = true;
+            }
+        }
+        self.streak = state.streak;
+        unlocked
+    }
+}
+
+// Per player name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Achievements {
+    pub players: BTreeMap<String, Progress>,
+}
+
+impl Achievements {
+    pub fn load(data_dir: &Path) -> io::Result<Achievements> {
+        storage::load(&data_dir.join(FILE_NAME))
+    }
+
+    // Adds the events of a game to the progress of `name`, and returns what it unlocked.
+    pub fn record(data_dir: &Path, name: &str, events: &[Event]) -> io::Result<Vec<&'static str>> {
+        storage::update(
+            &data_dir.join(FILE_NAME),
+            |achievements: &mut Achievements| {
+                achievements
+                    .players
+                    .entry(name.to_string())
+                    .or_default()
+                    .apply(events)
+            },
+        )
+    }
+
+    pub fn progress(&self, name: &str) -> Progress {
+        self.players.get(name).cloned().unwrap_or_default()
+    }
+}
+
+// Every achievement with what it takes, and the day it was unlocked (in local time).
+pub fn render(progress: &Progress) -> String {
+    let rows: Vec<(String, String, String)> = achievements()
+        .iter()
+        .map(|achievement| {
+            let id = achievement.id();
+            let status = match progress.unlocked.get(id) {
+                Some(date) => {
+                    let day = date.with_timezone(&Local).date_naive();
+                    i18n::message("achievement-unlocked-on", &[("date", &day)])
+                }
+                None => i18n::text("achievement-locked"),
+            };
+            (name(id), description(id), status)
+        })
+        .collect();
+    let width = |column: fn(&(String, String, String)) -> &String| {
+        rows.iter()
+            .map(|row| column(row).chars().count())
+            .max()
+            .unwrap_or(0)
+    };
+    let (names, descriptions) = (width(|row| &row.0), width(|row| &row.1));
+
+    let mut text = String::new();
+    for (name, description, status) in &rows {
+        text.push_str(&format!(
+            "{name:<names$}  {description:<descriptions$}  {status}\n"
+        ));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::*;
+
+    // The events of a game on `difficulty`, played with `guesses`.
+    fn play(difficulty: Difficulty, secret: i64, guesses: &[i64]) -> Vec<Event> {
+        let mut game = Game::new(difficulty.range(), secret).with_max_attempts(Some(20));
+        for &guess in guesses {
+            game.guess(guess).unwrap();
+        }
+        events(&game, difficulty)
+    }
+
+    fn won(guesses: &[i64]) -> Vec<Event> {
+        play(Difficulty::Normal, *guesses.last().unwrap(), guesses)
+    }
+
+    fn lost() -> Vec<Event> {
+        let guesses: Vec<i64> = (1..=20).collect();
+        play(Difficulty::Normal, 100, &guesses)
+    }
+
+    #[test]
+    fn a_game_turns_into_events() {
+        let events = won(&[50, 25]);
+        assert_eq!(
+            events,
+            [
+                Event::Guess {
+                    guess: 50,
+                    ordering: Ordering::Greater,
+                    bounds: Bounds { low: 1, high: 100 },
+                },
+                Event::Guess {
+                    guess: 25,
+                    ordering: Ordering::Equal,
+                    bounds: Bounds { low: 1, high: 49 },
+                },
+                Event::Won {
+                    attempts: 2,
+                    difficulty: Difficulty::Normal,
+                },
+            ]
+        );
+        assert_eq!(lost().last(), Some(&Event::Lost { attempts: 20 }));
+        // A game that's still going has nothing to say yet.
+        assert_eq!(play(Difficulty::Normal, 42, &[50]), []);
+    }
+
+    #[test]
+    fn the_first_guess() {
+        let mut progress = Progress::default();
+        assert_eq!(progress.apply(&won(&[42])), ["first-guess"]);
+        assert!(progress.unlocked.contains_key("first-guess"));
+        // Right in the middle, so by the book as well.
+        let mut progress = Progress::default();
+        assert_eq!(
+            progress.apply(&won(&[50])),
+            ["first-guess", "binary-search"]
+        );
+    }
+
+    #[test]
+    fn binary_search_takes_either_middle() {
+        // 1..=100 has two middles, 50 and 51; then 1..=49 has one, 25, and 1..=50 two again.
+        for guesses in [[50, 25], [51, 25], [51, 26]] {
+            let mut progress = Progress::default();
+            assert_eq!(
+                progress.apply(&won(&guesses)),
+                ["binary-search"],
+                "{guesses:?}"
+            );
+        }
+        for guesses in [[49, 25], [50, 24], [51, 27]] {
+            let mut progress = Progress::default();
+            assert!(progress.apply(&won(&guesses)).is_empty(), "{guesses:?}");
+        }
+        // Only as long as every guess halves: a detour in an earlier game doesn't count against
+        // the next one.
+        let mut progress = Progress::default();
+        let mut events = won(&[10, 50, 75]);
+        events.extend(won(&[50, 25]));
+        assert_eq!(progress.apply(&events), ["binary-search"]);
+    }
+
+    #[test]
+    fn halving_works_on_the_whole_i64_range() {
+        let bounds = Bounds {
+            low: i64::MIN,
+            high: i64::MAX,
+        };
+        assert!(halves(&bounds, -1));
+        assert!(halves(&bounds, 0));
+        assert!(!halves(&bounds, 1));
+        assert!(!halves(&bounds, i64::MIN));
+        // Outside of the bounds is never in the middle of them.
+        assert!(!halves(&Bounds { low: 5, high: 5 }, 4));
+    }
+
+    #[test]
+    fn a_streak_carries_over_between_sessions() {
+        let dir = env::temp_dir().join(format!(
+            "guessing_game-achievements-{}-streak",
+            process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let record = |events: &[Event]| Achievements::record(&dir, "ann", events).unwrap();
+        let streak = || Achievements::load(&dir).unwrap().progress("ann").streak;
+
+        // Nine wins, a loss, and nine more wins: never ten in a row.
+        for _ in 0..9 {
+            record(&won(&[40, 30]));
+        }
+        assert_eq!(streak(), 9);
+        record(&lost());
+        assert_eq!(streak(), 0);
+        for _ in 0..9 {
+            assert!(record(&won(&[40, 30])).is_empty());
+        }
+        // The tenth, in a session of its own.
+        assert_eq!(record(&won(&[40, 30])), ["streak"]);
+        assert_eq!(streak(), 10);
+        // Somebody else's games don't count.
+        assert_eq!(
+            Achievements::load(&dir).unwrap().progress("bob"),
+            Progress::default()
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn hard_takes_a_win_on_hard() {
+        let mut progress = Progress::default();
+        assert!(progress.apply(&won(&[40, 30])).is_empty());
+        assert_eq!(
+            progress.apply(&play(Difficulty::Hard, 1234, &[4000, 1234])),
+            ["hard"]
+        );
+        let guesses: Vec<i64> = (1..=20).collect();
+        let mut progress = Progress::default();
+        assert!(progress
+            .apply(&play(Difficulty::Hard, 9999, &guesses))
+            .is_empty());
+    }
+
+    #[test]
+    fn achievements_unlock_only_once() {
+        let mut progress = Progress::default();
+        let events = play(Difficulty::Hard, 5000, &[5000]);
+        assert_eq!(
+            progress.apply(&events),
+            ["first-guess", "binary-search", "hard"]
+        );
+        let unlocked = progress.unlocked.clone();
+
+        assert!(progress.apply(&events).is_empty());
+        let mut many = Vec::new();
+        for _ in 0..STREAK {
+            many.extend(won(&[50]));
+        }
+        assert_eq!(progress.apply(&many), ["streak"]);
+        assert!(progress.apply(&many).is_empty());
+        // Still unlocked on the day they were first unlocked.
+        for (id, date) in &unlocked {
+            assert_eq!(progress.unlocked[id], *date);
+        }
+        assert_eq!(progress.unlocked.len(), achievements().len());
+    }
+}
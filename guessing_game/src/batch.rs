@@ -0,0 +1,264 @@
+use std::cmp::Ordering;
+use std::io::{self, BufRead};
+
+use serde::Serialize;
+
+use crate::game::{Game, GuessError, Outcome};
+use crate::input::{self, InputError};
+
+// ========== Batch mode ==========
+/*
+`guessing_game --batch` reads guesses from stdin (or `--input <file>`), one per line, and prints
+one JSON transcript at the end instead of talking to a player. Nothing is prompted or localized.
+
+The output is meant for scripts, so its shape only changes together with `VERSION`:
+
+    {
+      "version": 1,
+      "seed": 9,
+      "min": 1,
+      "max": 100,
+      "max_attempts": null,
+      "guesses": [
+        { "line": 1, "input": "50", "guess": 50, "ordering": "Greater" },
+        { "line": 2, "input": "abc", "error": "not_a_number" },
+        { "line": 3, "input": "12", "guess": 12, "ordering": "Equal" }
+      ],
+      "outcome": "won",
+      "attempts": 2,
+      "secret": 12
+    }
+
+`ordering` compares the guess to the secret, like `Game::guess` does. Lines that aren't a valid guess
+get an `error` instead (see `error_code`) and don't count as an attempt; blank lines are skipped.
+`outcome` is `won`, `lost`, or `unfinished` when the input ran out first.
+Lines after the end of the game aren't read.
+ */
+pub const VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Transcript {
+    pub version: u32,
+    pub seed: u64,
+    pub min: i64,
+    pub max: i64,
+    pub max_attempts: Option<u32>,
+    pub guesses: Vec<Turn>,
+    pub outcome: BatchOutcome,
+    pub attempts: u32,
+    pub secret: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Turn {
+    // The line number in the input, starting at 1.
+    pub line: usize,
+    pub input: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guess: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ordering: Option<Answer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<&'static str>,
+}
+
+// `Ordering`, with the same names, but serializable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Answer {
+    Less,
+    Greater,
+    Equal,
+}
+
+impl From<Ordering> for Answer {
+    fn from(ordering: Ordering) -> Answer {
+        match ordering {
+            Ordering::Less => Answer::Less,
+            Ordering::Greater => Answer::Greater,
+            Ordering::Equal => Answer::Equal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchOutcome {
+    Won,
+    Lost,
+    Unfinished,
+}
+
+// Plays `game` with the lines from `reader`.
+// Only reading can fail; bad guesses end up in the transcript.
+pub fn run(mut game: Game, seed: u64, reader: impl BufRead) -> io::Result<Transcript> {
+    let mut guesses = Vec::new();
+    for (number, line) in reader.lines().enumerate() {
+        if game.is_finished() {
+            break;
+        }
+        let line = line?;
+        let text = line.trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        let mut turn = Turn {
+            line: number + 1,
+            input: text.to_string(),
+            guess: None,
+            ordering: None,
+            error: None,
+        };
+        match input::parse_guess(text, game.range()) {
+            Ok(guess) => {
+                turn.guess = Some(guess);
+                match game.guess(guess) {
+                    Ok(ordering) => turn.ordering = Some(ordering.into()),
+                    Err(err) => turn.error = Some(guess_error_code(&err)),
+                }
+            }
+            Err(err) => turn.error = Some(error_code(&err)),
+        }
+        guesses.push(turn);
+    }
+
+    let outcome = match game.outcome() {
+        Outcome::Won { .. } => BatchOutcome::Won,
+        Outcome::Lost { .. } => BatchOutcome::Lost,
+        Outcome::InProgress => BatchOutcome::Unfinished,
+    };
+    Ok(Transcript {
+        version: VERSION,
+        seed,
+        min: *game.range().start(),
+        max: *game.range().end(),
+        max_attempts: game.max_attempts(),
+        guesses,
+        outcome,
+        attempts: game.attempts(),
+        secret: game.secret(),
+    })
+}
+
+// The stable names of the ways a line can fail to be a guess.
+pub fn error_code(err: &InputError) -> &'static str {
+    match err {
+        InputError::Eof => "eof",
+        InputError::NotANumber(_) => "not_a_number",
+        InputError::Negative(_) => "negative",
+        InputError::Overflow(_) => "overflow",
+        InputError::OutOfRange { .. } => "out_of_range",
+        InputError::Io(_) => "io",
+        InputError::TimedOut => "timed_out",
+    }
+}
+
+pub fn guess_error_code(err: &GuessError) -> &'static str {
+    match err {
+        GuessError::Finished => "finished",
+        GuessError::OutOfRange { .. } => "out_of_range",
+        GuessError::TimeUp => "time_up",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::seed;
+
+    fn run(game: Game, seed: u64, input: &str) -> Value {
+        let transcript = super::run(game, seed, input.as_bytes()).unwrap();
+        serde_json::to_value(transcript).unwrap()
+    }
+
+    fn seeded(seed: u64) -> Game {
+        Game::from_rng(1..=100, &mut seed::rng_from_seed(seed))
+    }
+
+    // The example at the top of this file.
+    #[test]
+    fn a_won_game_matches_the_documented_schema() {
+        assert_eq!(
+            run(seeded(9), 9, "50\nabc\n12\n"),
+            json!({
+                "version": 1,
+                "seed": 9,
+                "min": 1,
+                "max": 100,
+                "max_attempts": null,
+                "guesses": [
+                    { "line": 1, "input": "50", "guess": 50, "ordering": "Greater" },
+                    { "line": 2, "input": "abc", "error": "not_a_number" },
+                    { "line": 3, "input": "12", "guess": 12, "ordering": "Equal" }
+                ],
+                "outcome": "won",
+                "attempts": 2,
+                "secret": 12
+            })
+        );
+    }
+
+    #[test]
+    fn a_lost_game_with_every_input_error() {
+        let game = seeded(9).with_max_attempts(Some(2));
+        let input = "\n   \n-3\n99999999999999999999\n101\n 1 \n2\n12\n";
+        assert_eq!(
+            run(game, 9, input),
+            json!({
+                "version": 1,
+                "seed": 9,
+                "min": 1,
+                "max": 100,
+                "max_attempts": 2,
+                "guesses": [
+                    { "line": 3, "input": "-3", "error": "negative" },
+                    { "line": 4, "input": "99999999999999999999", "error": "overflow" },
+                    { "line": 5, "input": "101", "error": "out_of_range" },
+                    { "line": 6, "input": "1", "guess": 1, "ordering": "Less" },
+                    { "line": 7, "input": "2", "guess": 2, "ordering": "Less" }
+                ],
+                "outcome": "lost",
+                "attempts": 2,
+                "secret": 12
+            })
+        );
+    }
+
+    #[test]
+    fn input_that_runs_out_leaves_the_game_unfinished() {
+        assert_eq!(
+            run(seeded(9), 9, "13\n"),
+            json!({
+                "version": 1,
+                "seed": 9,
+                "min": 1,
+                "max": 100,
+                "max_attempts": null,
+                "guesses": [
+                    { "line": 1, "input": "13", "guess": 13, "ordering": "Greater" }
+                ],
+                "outcome": "unfinished",
+                "attempts": 1,
+                "secret": 12
+            })
+        );
+    }
+
+    #[test]
+    fn a_guess_after_the_time_limit_is_an_error() {
+        let game = Game::new(1..=100, 12)
+            .with_time_limit(Some(Duration::from_secs(1)))
+            .with_elapsed(Duration::from_secs(2));
+        let transcript = run(game, 0, "12\n");
+        assert_eq!(
+            transcript["guesses"],
+            json!([{ "line": 1, "input": "12", "guess": 12, "error": "time_up" }])
+        );
+        assert_eq!(transcript["outcome"], "lost");
+        assert_eq!(transcript["attempts"], 0);
+    }
+}
@@ -0,0 +1,276 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+use rand::Rng;
+
+use crate::game::{Game, GuessError, Outcome};
+use crate::host::Host;
+use crate::seed::{self, GameRng};
+
+// ========== Bounds ==========
+/*
+All a player learns from a guess is the `Ordering`: the secret is bigger, smaller or equal.
+Bounds keeps track of what that leaves: the lowest and highest number the secret can still be.
+Every strategy gets the bounds and picks its next guess from them.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub low: i64,
+    pub high: i64,
+}
+
+impl Bounds {
+    pub fn new(range: &RangeInclusive<i64>) -> Bounds {
+        Bounds {
+            low: *range.start(),
+            high: *range.end(),
+        }
+    }
+
+    // `ordering` is the guess compared to the secret, exactly as `Game::guess` returns it.
+    pub fn update(&mut self, guess: i64, ordering: Ordering) {
+        match ordering {
+            Ordering::Less => self.low = self.low.max(guess.saturating_add(1)),
+            Ordering::Greater => self.high = self.high.min(guess.saturating_sub(1)),
+            Ordering::Equal => {
+                self.low = guess;
+                self.high = guess;
+            }
+        }
+    }
+
+    // The number `fraction` of the way from low to high. Computed in i128, so that it can't
+    // overflow, not even for i64::MIN..=i64::MAX.
+    pub fn split(&self, fraction: f64) -> i64 {
+        let width = self.high as i128 - self.low as i128;
+        (self.low as i128 + (width as f64 * fraction) as i128) as i64
+    }
+
+    pub fn midpoint(&self) -> i64 {
+        let width = self.high as i128 - self.low as i128;
+        (self.low as i128 + width / 2) as i64
+    }
+}
+
+// ========== Strategies ==========
+/*
+A strategy is anything that can pick a guess from the bounds. It gets its own seeded RNG,
+so strategies that guess at random can still be replayed.
+ */
+pub trait Strategy {
+    fn name(&self) -> &str;
+
+    fn next_guess(&mut self, bounds: &Bounds, rng: &mut GameRng) -> i64;
+}
+
+// Always halve the bounds: never needs more than ceil(log2(range size)) attempts.
+pub struct BinarySearch;
+
+impl Strategy for BinarySearch {
+    fn name(&self) -> &str {
+        "binary"
+    }
+
+    fn next_guess(&mut self, bounds: &Bounds, _rng: &mut GameRng) -> i64 {
+        bounds.midpoint()
+    }
+}
+
+// Any number that is still possible, picked at random.
+pub struct Random;
+
+impl Strategy for Random {
+    fn name(&self) -> &str {
+        "random"
+    }
+
+    fn next_guess(&mut self, bounds: &Bounds, rng: &mut GameRng) -> i64 {
+        rng.gen_range(bounds.low..=bounds.high)
+    }
+}
+
+// Count up from the lowest possible number, one at a time.
+pub struct Linear;
+
+impl Strategy for Linear {
+    fn name(&self) -> &str {
+        "linear"
+    }
+
+    fn next_guess(&mut self, bounds: &Bounds, _rng: &mut GameRng) -> i64 {
+        bounds.low
+    }
+}
+
+// Like binary search, but splits a quarter of the way up instead of in the middle.
+// It still always makes progress, just slower: a baseline to compare the others against.
+pub struct Biased;
+
+impl Strategy for Biased {
+    fn name(&self) -> &str {
+        "biased"
+    }
+
+    fn next_guess(&mut self, bounds: &Bounds, _rng: &mut GameRng) -> i64 {
+        bounds.split(0.25)
+    }
+}
+
+pub fn strategies() -> Vec<Box<dyn Strategy>> {
+    vec![
+        Box::new(BinarySearch),
+        Box::new(Random),
+        Box::new(Linear),
+        Box::new(Biased),
+    ]
+}
+
+pub fn strategy(name: &str) -> Option<Box<dyn Strategy>> {
+    strategies()
+        .into_iter()
+        .find(|strategy| strategy.name() == name)
+}
+
+// ========== Playing ==========
+/*
+A bot plays exactly like the human loop in main.rs: guess, get an `Ordering` back, repeat.
+The only difference is that the bounds are updated with the answer, instead of printed.
+ */
+pub fn play(
+    strategy: &mut dyn Strategy,
+    game: &mut Game,
+    rng: &mut GameRng,
+) -> Result<Outcome, GuessError> {
+    let mut bounds = Bounds::new(game.range());
+    while !game.is_finished() {
+        let guess = strategy.next_guess(&bounds, rng);
+        let ordering = game.guess(guess)?;
+        bounds.update(guess, ordering);
+    }
+    Ok(game.outcome())
+}
+
+// ========== Benchmark ==========
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub strategy: String,
+    pub games: u32,
+    pub mean: f64,
+    pub best: u32,
+    pub worst: u32,
+    // How many games took each number of attempts.
+    pub distribution: BTreeMap<u32, u32>,
+}
+
+/*
+Plays `games` games with the strategy. The secrets come from `seed`, so every strategy
+benchmarked with the same seed gets the exact same list of secrets to find.
+With the evil host there are no secrets to draw; every game is the worst case instead.
+ */
+pub fn benchmark(
+    strategy: &mut dyn Strategy,
+    range: &RangeInclusive<i64>,
+    host: Host,
+    games: u32,
+    seed: u64,
+) -> Result<Report, GuessError> {
+    let mut secrets = seed::rng_from_seed(seed);
+    let mut strategy_rng = seed::rng_from_seed(seed.wrapping_add(1));
+    let mut distribution = BTreeMap::new();
+    let mut total: u64 = 0;
+
+    for _ in 0..games {
+        let mut game = Game::hosted(range.clone(), host, &mut secrets);
+        play(strategy, &mut game, &mut strategy_rng)?;
+        total += game.attempts() as u64;
+        *distribution.entry(game.attempts()).or_insert(0) += 1;
+    }
+
+    Ok(Report {
+        strategy: strategy.name().to_string(),
+        games,
+        mean: total as f64 / games.max(1) as f64,
+        best: distribution.keys().next().copied().unwrap_or(0),
+        worst: distribution.keys().next_back().copied().unwrap_or(0),
+        distribution,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RANGE: RangeInclusive<i64> = 1..=100;
+
+    fn run(name: &str, host: Host, seed: u64) -> Report {
+        let mut strategy = strategy(name).unwrap();
+        benchmark(strategy.as_mut(), &RANGE, host, 500, seed).unwrap()
+    }
+
+    #[test]
+    fn bounds_follow_the_answers() {
+        let mut bounds = Bounds::new(&RANGE);
+        bounds.update(50, Ordering::Less);
+        assert_eq!(bounds, Bounds { low: 51, high: 100 });
+        bounds.update(75, Ordering::Greater);
+        assert_eq!(bounds, Bounds { low: 51, high: 74 });
+        assert_eq!(bounds.midpoint(), 62);
+        assert_eq!(bounds.split(0.25), 56);
+        bounds.update(60, Ordering::Equal);
+        assert_eq!(bounds, Bounds { low: 60, high: 60 });
+
+        // The full range doesn't overflow.
+        let mut full = Bounds::new(&(i64::MIN..=i64::MAX));
+        assert_eq!(full.midpoint(), -1);
+        full.update(i64::MAX, Ordering::Less);
+        assert_eq!(full.low, i64::MAX);
+    }
+
+    #[test]
+    fn binary_search_needs_at_most_seven_guesses() {
+        for host in [Host::Fair, Host::Evil] {
+            let report = run("binary", host, 1);
+            assert_eq!(report.games, 500);
+            assert!(report.worst <= 7, "{host}: {report:?}");
+            assert_eq!(report.distribution.values().sum::<u32>(), 500);
+        }
+        // Against the evil host, every game is the worst case.
+        let evil = run("binary", Host::Evil, 1);
+        assert_eq!((evil.best, evil.worst), (7, 7));
+    }
+
+    #[test]
+    fn every_strategy_finds_every_secret() {
+        for mut strategy in strategies() {
+            for secret in RANGE {
+                let mut game = Game::new(RANGE, secret).with_max_attempts(Some(100));
+                let outcome = play(strategy.as_mut(), &mut game, &mut seed::rng_from_seed(3));
+                assert!(
+                    matches!(outcome, Ok(Outcome::Won { .. })),
+                    "{} on {secret}",
+                    strategy.name()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn strategies_rank_as_expected() {
+        let mean = |name| run(name, Host::Fair, 9).mean;
+        assert!(mean("binary") < mean("biased"));
+        assert!(mean("biased") < mean("random"));
+        assert!(mean("random") < mean("linear"));
+        // Counting up takes as many guesses as the secret: about half the range.
+        assert!((mean("linear") - 50.5).abs() < 5.0);
+    }
+
+    #[test]
+    fn the_same_seed_gives_the_same_report() {
+        for name in ["binary", "random", "linear", "biased"] {
+            assert_eq!(run(name, Host::Fair, 5), run(name, Host::Fair, 5));
+        }
+        assert_ne!(run("random", Host::Fair, 5), run("random", Host::Fair, 6));
+        assert!(strategy("psychic").is_none());
+    }
+}
@@ -0,0 +1,339 @@
+use std::error::Error;
+use std::fmt;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::game::Outcome;
+use crate::i18n;
+
+// ========== Bulls and cows ==========
+/*
+The second game mode. The secret is a code of distinct symbols (digits by default), and every
+guess is answered with the number of
+- bulls: right symbol in the right place, and
+- cows: right symbol, but in the wrong place.
+The game is won when all symbols are bulls.
+
+Both the length of the code and the alphabet it's made of can be configured, e.g. a 5 letter code
+from "abcdefgh". The secret comes from the same seeded `GameRng` as the numeric game.
+ */
+pub const DEFAULT_ALPHABET: &str = "0123456789";
+pub const DEFAULT_LENGTH: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Feedback {
+    pub bulls: usize,
+    pub cows: usize,
+}
+
+impl fmt::Display for Feedback {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = i18n::message("feedback", &[("bulls", &self.bulls), ("cows", &self.cows)]);
+        f.write_str(&text)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodeError {
+    // The game was already won or lost.
+    Finished,
+    WrongLength { expected: usize, got: usize },
+    NotInAlphabet(char),
+    // Codes never repeat a symbol, so neither should a guess.
+    Repeated(char),
+}
+
+impl fmt::Display for CodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            CodeError::Finished => i18n::text("game-finished"),
+            CodeError::WrongLength { expected, got } => {
+                i18n::message("code-wrong-length", &[("expected", expected), ("got", got)])
+            }
+            CodeError::NotInAlphabet(symbol) => {
+                i18n::message("code-not-in-alphabet", &[("symbol", symbol)])
+            }
+            CodeError::Repeated(symbol) => i18n::message("code-repeated", &[("symbol", symbol)]),
+        };
+        f.write_str(&text)
+    }
+}
+
+impl Error for CodeError {}
+
+#[derive(Debug, Clone)]
+pub struct BullsAndCows {
+    secret: Vec<char>,
+    alphabet: Vec<char>,
+    max_attempts: Option<u32>,
+    history: Vec<(String, Feedback)>,
+    outcome: Outcome,
+}
+
+impl BullsAndCows {
+    // The code needs `length` different symbols, so the alphabet has to have at least that many.
+    pub fn new(secret: &str, alphabet: &str) -> Result<BullsAndCows, String> {
+        let alphabet = distinct(alphabet);
+        let game = BullsAndCows {
+            secret: Vec::new(),
+            alphabet,
+            max_attempts: None,
+            history: Vec::new(),
+            outcome: Outcome::InProgress,
+        };
+        let secret = game
+            .check(secret)
+            .map_err(|err| i18n::message("code-invalid-secret", &[("error", &err)]))?;
+        Ok(BullsAndCows { secret, ..game })
+    }
+
+    pub fn from_rng<R: Rng>(
+        length: usize,
+        alphabet: &str,
+        rng: &mut R,
+    ) -> Result<BullsAndCows, String> {
+        let symbols = distinct(alphabet);
+        if length == 0 || length > symbols.len() {
+            return Err(i18n::message(
+                "code-too-long",
+                &[("length", &length), ("symbols", &symbols.len())],
+            ));
+        }
+        let secret: String = symbols.choose_multiple(rng, length).collect();
+        BullsAndCows::new(&secret, alphabet)
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: Option<u32>) -> BullsAndCows {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    pub fn guess(&mut self, guess: &str) -> Result<Feedback, CodeError> {
+        if self.is_finished() {
+            return Err(CodeError::Finished);
+        }
+        let guess = self.check(guess)?;
+
+        let bulls = guess
+            .iter()
+            .zip(&self.secret)
+            .filter(|(guessed, secret)| guessed == secret)
+            .count();
+        let common = guess
+            .iter()
+            .filter(|symbol| self.secret.contains(symbol))
+            .count();
+        let feedback = Feedback {
+            bulls,
+            cows: common - bulls,
+        };
+        self.history.push((guess.iter().collect(), feedback));
+
+        let attempts = self.attempts();
+        if bulls == self.secret.len() {
+            self.outcome = Outcome::Won { attempts };
+        } else if self.max_attempts.is_some_and(|max| attempts >= max) {
+            self.outcome = Outcome::Lost { attempts };
+        }
+
+        Ok(feedback)
+    }
+
+    // A guess is valid when it's as long as the code, and only uses distinct symbols of the alphabet.
+    fn check(&self, guess: &str) -> Result<Vec<char>, CodeError> {
+        let guess: Vec<char> = guess.trim().chars().collect();
+        let expected = if self.secret.is_empty() {
+            guess.len()
+        } else {
+            self.secret.len()
+        };
+        if guess.len() != expected || guess.is_empty() {
+            return Err(CodeError::WrongLength {
+                expected,
+                got: guess.len(),
+            });
+        }
+        for (i, symbol) in guess.iter().enumerate() {
+            if !self.alphabet.contains(symbol) {
+                return Err(CodeError::NotInAlphabet(*symbol));
+            }
+            if guess[..i].contains(symbol) {
+                return Err(CodeError::Repeated(*symbol));
+            }
+        }
+        Ok(guess)
+    }
+
+    pub fn outcome(&self) -> Outcome {
+        self.outcome
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.history.len() as u32
+    }
+
+    pub fn attempts_left(&self) -> Option<u32> {
+        self.max_attempts
+            .map(|max| max.saturating_sub(self.attempts()))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.outcome != Outcome::InProgress
+    }
+
+    pub fn history(&self) -> &[(String, Feedback)] {
+        &self.history
+    }
+
+    pub fn secret(&self) -> String {
+        self.secret.iter().collect()
+    }
+
+    pub fn length(&self) -> usize {
+        self.secret.len()
+    }
+
+    pub fn alphabet(&self) -> String {
+        self.alphabet.iter().collect()
+    }
+}
+
+// The alphabet without duplicates, in the order the symbols first appear.
+fn distinct(alphabet: &str) -> Vec<char> {
+    let mut symbols: Vec<char> = Vec::new();
+    for symbol in alphabet.chars().filter(|c| !c.is_whitespace()) {
+        if !symbols.contains(&symbol) {
+            symbols.push(symbol);
+        }
+    }
+    symbols
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seed;
+
+    fn feedback(bulls: usize, cows: usize) -> Feedback {
+        Feedback { bulls, cows }
+    }
+
+    fn game() -> BullsAndCows {
+        BullsAndCows::new("1234", DEFAULT_ALPHABET).unwrap()
+    }
+
+    #[test]
+    fn bulls_and_cows_are_counted() {
+        let mut game = game();
+        for (guess, expected) in [
+            ("5678", feedback(0, 0)),
+            ("4321", feedback(0, 4)),
+            ("1243", feedback(2, 2)),
+            ("1567", feedback(1, 0)),
+            ("5612", feedback(0, 2)),
+            ("1235", feedback(3, 0)),
+        ] {
+            assert_eq!(game.guess(guess).unwrap(), expected, "{guess}");
+        }
+        assert_eq!(game.outcome(), Outcome::InProgress);
+        assert_eq!(game.guess(" 1234\n").unwrap(), feedback(4, 0));
+        assert_eq!(game.outcome(), Outcome::Won { attempts: 7 });
+        assert_eq!(game.history()[1], ("4321".to_string(), feedback(0, 4)));
+        assert_eq!(game.guess("1234"), Err(CodeError::Finished));
+    }
+
+    #[test]
+    fn invalid_guesses_are_not_counted() {
+        let mut game = game();
+        for (guess, err) in [
+            (
+                "123",
+                CodeError::WrongLength {
+                    expected: 4,
+                    got: 3,
+                },
+            ),
+            (
+                "12345",
+                CodeError::WrongLength {
+                    expected: 4,
+                    got: 5,
+                },
+            ),
+            (
+                "",
+                CodeError::WrongLength {
+                    expected: 4,
+                    got: 0,
+                },
+            ),
+            ("12a4", CodeError::NotInAlphabet('a')),
+            ("1 34", CodeError::NotInAlphabet(' ')),
+            ("1121", CodeError::Repeated('1')),
+            ("1233", CodeError::Repeated('3')),
+        ] {
+            assert_eq!(game.guess(guess), Err(err), "{guess:?}");
+        }
+        assert_eq!(game.attempts(), 0);
+    }
+
+    #[test]
+    fn other_lengths_and_alphabets() {
+        let mut game = BullsAndCows::new("cafe", "abcdef").unwrap();
+        assert_eq!(game.length(), 4);
+        assert_eq!(game.guess("face").unwrap(), feedback(2, 2));
+        assert_eq!(game.guess("efac").unwrap(), feedback(0, 4));
+        assert_eq!(game.guess("bade").unwrap(), feedback(2, 0));
+        assert_eq!(game.guess("cage"), Err(CodeError::NotInAlphabet('g')));
+
+        // Duplicates and whitespace in the alphabet are left out.
+        let game = BullsAndCows::new("ab", "a b a c").unwrap();
+        assert_eq!(game.alphabet(), "abc");
+
+        // Neither the secret nor a code drawn at random can be longer than the alphabet.
+        assert!(BullsAndCows::new("aab", "abc").is_err());
+        assert!(BullsAndCows::new("abx", "abc").is_err());
+        let mut rng = seed::rng_from_seed(1);
+        assert!(BullsAndCows::from_rng(4, "abc", &mut rng).is_err());
+        assert!(BullsAndCows::from_rng(0, "abc", &mut rng).is_err());
+    }
+
+    #[test]
+    fn random_codes_are_distinct_and_seeded() {
+        let code = |seed| {
+            BullsAndCows::from_rng(
+                DEFAULT_LENGTH,
+                DEFAULT_ALPHABET,
+                &mut seed::rng_from_seed(seed),
+            )
+            .unwrap()
+            .secret()
+        };
+        for seed in 0..50 {
+            let secret = code(seed);
+            assert_eq!(secret.len(), DEFAULT_LENGTH);
+            assert_eq!(distinct(&secret).len(), DEFAULT_LENGTH, "{secret}");
+            assert!(secret
+                .chars()
+                .all(|symbol| DEFAULT_ALPHABET.contains(symbol)));
+            assert_eq!(code(seed), secret);
+        }
+        // The whole alphabet, shuffled.
+        let all =
+            BullsAndCows::from_rng(10, DEFAULT_ALPHABET, &mut seed::rng_from_seed(1)).unwrap();
+        let mut symbols: Vec<char> = all.secret().chars().collect();
+        symbols.sort_unstable();
+        assert_eq!(symbols.into_iter().collect::<String>(), DEFAULT_ALPHABET);
+    }
+
+    #[test]
+    fn the_last_attempt_loses() {
+        let mut game = game().with_max_attempts(Some(2));
+        game.guess("5678").unwrap();
+        assert_eq!(game.attempts_left(), Some(1));
+        game.guess("1243").unwrap();
+        assert_eq!(game.outcome(), Outcome::Lost { attempts: 2 });
+        assert_eq!(game.attempts_left(), Some(0));
+    }
+}
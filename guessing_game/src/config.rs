@@ -0,0 +1,505 @@
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::NaiveDate;
+
+use crate::bulls_cows;
+use crate::difficulty::Difficulty;
+use crate::host::Host;
+use crate::http;
+use crate::i18n;
+use crate::leaderboard;
+use crate::plugin;
+use crate::secrets::Secrets;
+use crate::stats;
+
+// ========== Command line configuration ==========
+/*
+Same approach as `Config::build` in chapter 12 of the book: take the iterator from `env::args()`,
+skip the program name and walk through the flags. Any problem is returned as a `ConfigError`, main
+decides how to exit. It's only turned into a message when it's shown: the language isn't known
+before the arguments are parsed.
+
+Without a subcommand the game is played. Subcommands (like `leaderboard`) pick something else to do.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    #[default]
+    Play,
+    // `leaderboard` or `--leaderboard`: print the high-score table instead of playing.
+    Leaderboard,
+    // `bot`: let the solver strategies play many games and compare how many attempts they need.
+    Bot,
+    // `reverse`: the player picks a number and the program guesses it.
+    Reverse,
+    // `bulls-and-cows`: guess a code of distinct symbols instead of a number.
+    BullsAndCows,
+    // `hot-cold`: every guess is answered with warmer or colder than the one before.
+    HotCold,
+    // `tui`: the numeric game, full-screen in the terminal.
+    Tui,
+    // `serve`: host multiplayer rounds over TCP.
+    Serve,
+    // `join`: play on a server started with `serve`.
+    Join,
+    // `http`: serve games over an HTTP JSON API.
+    Http,
+    // `speedrun`: play a number of rounds against one clock.
+    SpeedRun,
+    // `batch` or `--batch`: play the guesses from stdin (or `--input`) and print a JSON transcript.
+    Batch,
+    // `stats`: sum up the games of a player.
+    Stats,
+    // `replay <file>`: play a transcript written with `--record` again and check every answer.
+    Replay,
+    // `tournament`: a round robin between the `--bot` plugins.
+    Tournament,
+    // `achievements`: list what a player has unlocked.
+    Achievements,
+    // `ratings`: the rating table, or with `--name` the rating history of one player.
+    Ratings,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub mode: Mode,
+    // `--seed <u64>`; None means a random seed is picked (and printed) at start.
+    pub seed: Option<u64>,
+    // `--difficulty <easy|normal|hard>`; becomes Custom as soon as `--min` or `--max` is given.
+    pub difficulty: Difficulty,
+    // The range the secret is drawn from: the preset's range, with `--min`/`--max` applied on top.
+    pub range: RangeInclusive<i64>,
+    // `--max-attempts <n>`; None means unlimited, like the original game.
+    pub max_attempts: Option<u32>,
+    // `--time-limit <seconds>`: a countdown for each game; None means no clock to beat.
+    pub time_limit: Option<Duration>,
+    // `--rounds <n>`: how many rounds a speed run has.
+    pub rounds: u32,
+    // `--host <fair|evil>`: who answers the guesses, for the player as well as for the bots.
+    pub host: Host,
+    // `--secrets <source>`: how a fair host picks the secret, see secrets.rs.
+    pub secrets: Secrets,
+    // `--name <name>`: who is playing. Also filters the leaderboard.
+    pub name: Option<String>,
+    // `--data-dir <path>`: where the leaderboard is kept, instead of the user's data dir.
+    pub data_dir: Option<PathBuf>,
+    // What the leaderboard shows; `--top <n>` sets the number of rows.
+    pub filter: leaderboard::Filter,
+    // Whose games `stats` sums up; `--from <date>` and `--to <date>` (like 2024-05-01) limit them.
+    pub stats: stats::Filter,
+    // `--strategy <name>`: the bot strategy to benchmark; None runs all of them.
+    pub strategy: Option<String>,
+    // `--games <n>`: how many games each bot plays (per match, in a tournament).
+    pub games: u32,
+    // `--bot <file.wasm>`, once per bot: the plugins that play a tournament.
+    pub bots: Vec<PathBuf>,
+    // What a plugin may use: `--fuel <n>` per call, and `--max-memory <MiB>`.
+    pub limits: plugin::Limits,
+    // `--length <n>`: the number of symbols in a bulls-and-cows code.
+    pub length: usize,
+    // `--alphabet <symbols>`: the symbols a bulls-and-cows code is made of.
+    pub alphabet: String,
+    // `--grid`: the hot-cold secret is a point on a grid instead of a number.
+    pub grid: bool,
+    // `--addr <host:port>`: where `serve` and `http` listen and `join` connects to.
+    // None means the default of the mode.
+    pub addr: Option<String>,
+    // `--session-timeout <seconds>`: how long an unused HTTP game is kept.
+    pub session_timeout: Duration,
+    // `--resume <file>`: continue a game saved with `:save` or `:quit`.
+    pub resume: Option<PathBuf>,
+    // `--lang <code>`: the language of the messages, e.g. `nl`. None means `LANG` decides.
+    pub lang: Option<String>,
+    // `--input <file>`: where `batch` reads its guesses from, instead of stdin.
+    // For `replay <file>` it's the transcript.
+    pub input: Option<PathBuf>,
+    // `--record <file>`: write a transcript of the game to replay later.
+    pub record: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            mode: Mode::default(),
+            seed: None,
+            difficulty: Difficulty::default(),
+            range: Difficulty::default().range(),
+            max_attempts: None,
+            time_limit: None,
+            rounds: 5,
+            host: Host::default(),
+            secrets: Secrets::default(),
+            name: None,
+            data_dir: None,
+            filter: leaderboard::Filter::default(),
+            stats: stats::Filter::default(),
+            strategy: None,
+            games: 10_000,
+            bots: Vec::new(),
+            limits: plugin::Limits::default(),
+            length: bulls_cows::DEFAULT_LENGTH,
+            alphabet: bulls_cows::DEFAULT_ALPHABET.to_string(),
+            grid: false,
+            addr: None,
+            session_timeout: http::DEFAULT_SESSION_TIMEOUT,
+            resume: None,
+            lang: None,
+            input: None,
+            record: None,
+        }
+    }
+}
+
+impl Config {
+    pub fn build(mut args: impl Iterator<Item = String>) -> Result<Config, ConfigError> {
+        args.next(); // The first value is the name of the program
+
+        let mut config = Config::default();
+        let mut difficulty = None;
+        let mut min = None;
+        let mut max = None;
+        let mut secrets = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "leaderboard" | "--leaderboard" => config.mode = Mode::Leaderboard,
+                "bot" => config.mode = Mode::Bot,
+                "reverse" => config.mode = Mode::Reverse,
+                "bulls-and-cows" => config.mode = Mode::BullsAndCows,
+                "hot-cold" => config.mode = Mode::HotCold,
+                "tui" => config.mode = Mode::Tui,
+                "serve" => config.mode = Mode::Serve,
+                "join" => config.mode = Mode::Join,
+                "http" => config.mode = Mode::Http,
+                "speedrun" => config.mode = Mode::SpeedRun,
+                "batch" | "--batch" => config.mode = Mode::Batch,
+                "stats" => config.mode = Mode::Stats,
+                "tournament" => config.mode = Mode::Tournament,
+                "achievements" => config.mode = Mode::Achievements,
+                "ratings" => config.mode = Mode::Ratings,
+                "replay" => {
+                    config.mode = Mode::Replay;
+                    config.input = Some(parse_value(&arg, args.next())?);
+                }
+                "--seed" => config.seed = Some(parse_value(&arg, args.next())?),
+                "--difficulty" => difficulty = Some(parse_value(&arg, args.next())?),
+                "--min" => min = Some(parse_value(&arg, args.next())?),
+                "--max" => max = Some(parse_value(&arg, args.next())?),
+                "--max-attempts" => config.max_attempts = Some(parse_value(&arg, args.next())?),
+                "--time-limit" => {
+                    config.time_limit = Some(Duration::from_secs(parse_value(&arg, args.next())?))
+                }
+                "--rounds" => config.rounds = parse_value(&arg, args.next())?,
+                "--host" => config.host = parse_value(&arg, args.next())?,
+                "--secrets" => secrets = Some(parse_value(&arg, args.next())?),
+                "--name" => config.name = Some(parse_value(&arg, args.next())?),
+                "--data-dir" => config.data_dir = Some(parse_value(&arg, args.next())?),
+                "--top" => config.filter.top = parse_value(&arg, args.next())?,
+                "--from" => config.stats.from = Some(parse_value(&arg, args.next())?),
+                "--to" => config.stats.to = Some(parse_value(&arg, args.next())?),
+                "--strategy" => config.strategy = Some(parse_value(&arg, args.next())?),
+                "--games" => config.games = parse_value(&arg, args.next())?,
+                "--bot" => config.bots.push(parse_value(&arg, args.next())?),
+                "--fuel" => config.limits.fuel = parse_value(&arg, args.next())?,
+                "--max-memory" => {
+                    let mebibytes: usize = parse_value(&arg, args.next())?;
+                    config.limits.memory = mebibytes.saturating_mul(1 << 20);
+                }
+                "--length" => config.length = parse_value(&arg, args.next())?,
+                "--alphabet" => config.alphabet = parse_value(&arg, args.next())?,
+                "--grid" => config.grid = true,
+                "--addr" => config.addr = Some(parse_value(&arg, args.next())?),
+                "--resume" => config.resume = Some(parse_value(&arg, args.next())?),
+                "--lang" => config.lang = Some(parse_value(&arg, args.next())?),
+                "--input" => config.input = Some(parse_value(&arg, args.next())?),
+                "--record" => config.record = Some(parse_value(&arg, args.next())?),
+                "--session-timeout" => {
+                    config.session_timeout = Duration::from_secs(parse_value(&arg, args.next())?)
+                }
+                _ => return Err(ConfigError::UnknownArgument(arg)),
+            }
+        }
+
+        // Flags can come in any order, so the range is only worked out once all of them are read.
+        config.difficulty = difficulty.unwrap_or_default();
+        config.range = config.difficulty.range();
+        if min.is_some() || max.is_some() {
+            config.difficulty = Difficulty::Custom;
+            config.range =
+                min.unwrap_or(*config.range.start())..=max.unwrap_or(*config.range.end());
+        }
+        if config.range.is_empty() {
+            return Err(ConfigError::EmptyRange {
+                min: *config.range.start(),
+                max: *config.range.end(),
+            });
+        }
+
+        if config.max_attempts == Some(0) {
+            return Err(ConfigError::AtLeastOne("--max-attempts"));
+        }
+        if config.time_limit == Some(Duration::ZERO) {
+            return Err(ConfigError::NoTime);
+        }
+        if config.rounds == 0 {
+            return Err(ConfigError::AtLeastOne("--rounds"));
+        }
+        if config.games == 0 {
+            return Err(ConfigError::AtLeastOne("--games"));
+        }
+
+        if config.mode == Mode::Tournament && config.bots.len() < 2 {
+            return Err(ConfigError::TooFewBots);
+        }
+        if config.limits.fuel == 0 {
+            return Err(ConfigError::AtLeastOne("--fuel"));
+        }
+
+        // Only the plain game saves and records; the other modes would silently ignore them.
+        if config.mode != Mode::Play {
+            if config.record.is_some() {
+                return Err(ConfigError::OnlyWhenPlaying("--record"));
+            }
+            if config.resume.is_some() {
+                return Err(ConfigError::OnlyWhenPlaying("--resume"));
+            }
+        }
+        // The bots, the servers and a resumed game don't draw secrets from a source.
+        if let Some(secrets) = secrets {
+            let draws = matches!(
+                config.mode,
+                Mode::Play | Mode::Tui | Mode::HotCold | Mode::SpeedRun | Mode::Batch
+            );
+            if !draws || config.resume.is_some() {
+                return Err(ConfigError::SecretsUnused);
+            }
+            config.secrets = secrets;
+        }
+        // A resumed game has no seed to start a transcript from.
+        if config.record.is_some() && config.resume.is_some() {
+            return Err(ConfigError::RecordAndResume);
+        }
+
+        if let Some(lang) = &config.lang {
+            if i18n::language(lang).is_none() {
+                return Err(ConfigError::UnsupportedLanguage(lang.clone()));
+            }
+        }
+
+        // The leaderboard is only filtered on the difficulty when one was asked for explicitly.
+        config.filter.name = config.name.clone();
+        config.filter.difficulty = difficulty;
+
+        // Statistics are always about one player: the one playing, unless `--name` says otherwise.
+        config.stats.name = config.player_name();
+        if let (Some(from), Some(to)) = (config.stats.from, config.stats.to) {
+            if from > to {
+                return Err(ConfigError::DatesReversed { from, to });
+            }
+        }
+
+        Ok(config)
+    }
+
+    // The name to record a win under: `--name`, or else the name of the logged in user.
+    pub fn player_name(&self) -> String {
+        self.name
+            .clone()
+            .or_else(|| env::var("USER").ok())
+            .or_else(|| env::var("USERNAME").ok())
+            .unwrap_or_else(|| "player".to_string())
+    }
+}
+
+// Flags like `--seed` need a value right after them. Parse it into whatever type the field has.
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, ConfigError> {
+    let value = value.ok_or_else(|| ConfigError::MissingValue(flag.to_string()))?;
+    value.parse().map_err(|_| ConfigError::InvalidValue {
+        flag: flag.to_string(),
+        value,
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    UnknownArgument(String),
+    // A flag at the very end, without the value it needs.
+    MissingValue(String),
+    InvalidValue { flag: String, value: String },
+    EmptyRange { min: i64, max: i64 },
+    // A count given as 0, like `--games 0`.
+    AtLeastOne(&'static str),
+    // `--time-limit 0`.
+    NoTime,
+    TooFewBots,
+    // A flag that only the plain game uses, given with another mode.
+    OnlyWhenPlaying(&'static str),
+    RecordAndResume,
+    // `--secrets` in a mode that never draws a secret from it.
+    SecretsUnused,
+    UnsupportedLanguage(String),
+    DatesReversed { from: NaiveDate, to: NaiveDate },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            ConfigError::UnknownArgument(argument) => {
+                i18n::message("config-unknown-argument", &[("argument", argument)])
+            }
+            ConfigError::MissingValue(flag) => {
+                i18n::message("config-missing-value", &[("flag", flag)])
+            }
+            ConfigError::InvalidValue { flag, value } => {
+                i18n::message("config-invalid-value", &[("flag", flag), ("value", value)])
+            }
+            ConfigError::EmptyRange { min, max } => {
+                i18n::message("config-empty-range", &[("min", min), ("max", max)])
+            }
+            ConfigError::AtLeastOne(flag) => {
+                i18n::message("config-at-least-one", &[("flag", flag)])
+            }
+            ConfigError::NoTime => i18n::text("config-no-time"),
+            ConfigError::TooFewBots => i18n::text("config-too-few-bots"),
+            ConfigError::OnlyWhenPlaying(flag) => {
+                i18n::message("config-only-when-playing", &[("flag", flag)])
+            }
+            ConfigError::RecordAndResume => i18n::text("config-record-and-resume"),
+            ConfigError::SecretsUnused => i18n::text("config-secrets-unused"),
+            ConfigError::UnsupportedLanguage(lang) => {
+                let supported: Vec<&str> = i18n::CATALOGS.iter().map(|(code, _)| *code).collect();
+                i18n::message(
+                    "config-unsupported-language",
+                    &[("lang", lang), ("supported", &supported.join(", "))],
+                )
+            }
+            ConfigError::DatesReversed { from, to } => {
+                i18n::message("config-dates-reversed", &[("from", from), ("to", to)])
+            }
+        };
+        f.write_str(&text)
+    }
+}
+
+impl Error for ConfigError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(args: &[&str]) -> Result<Config, ConfigError> {
+        let args = ["guessing_game"]
+            .iter()
+            .chain(args)
+            .map(|arg| arg.to_string());
+        Config::build(args)
+    }
+
+    #[test]
+    fn only_the_plain_game_records_and_resumes() {
+        assert!(build(&["--record", "game.txt"]).is_ok());
+        assert!(build(&["--resume", "game.save"]).is_ok());
+        for mode in ["tui", "speedrun", "bulls-and-cows", "hot-cold"] {
+            for flag in ["--record", "--resume"] {
+                assert_eq!(
+                    build(&[mode, flag, "file"]),
+                    Err(ConfigError::OnlyWhenPlaying(flag)),
+                    "{mode}"
+                );
+            }
+        }
+        assert_eq!(
+            build(&["--record", "game.txt", "--resume", "game.save"]),
+            Err(ConfigError::RecordAndResume)
+        );
+    }
+
+    #[test]
+    fn only_modes_that_draw_a_secret_take_secrets() {
+        for mode in ["tui", "hot-cold", "speedrun", "batch"] {
+            let config = build(&[mode, "--secrets", "edges"]).unwrap();
+            assert_eq!(config.secrets, Secrets::Edges, "{mode}");
+        }
+        assert_eq!(
+            build(&["--secrets", "middle"]).unwrap().secrets,
+            Secrets::Middle
+        );
+        assert_eq!(build(&[]).unwrap().secrets, Secrets::Uniform);
+
+        for mode in [
+            "bot",
+            "serve",
+            "http",
+            "join",
+            "reverse",
+            "bulls-and-cows",
+            "stats",
+        ] {
+            assert_eq!(
+                build(&[mode, "--secrets", "file:/does/not/exist"]),
+                Err(ConfigError::SecretsUnused),
+                "{mode}"
+            );
+        }
+        assert_eq!(
+            build(&[
+                "tournament",
+                "--bot",
+                "a.wasm",
+                "--bot",
+                "b.wasm",
+                "--secrets",
+                "edges"
+            ]),
+            Err(ConfigError::SecretsUnused)
+        );
+        assert_eq!(
+            build(&["--resume", "game.save", "--secrets", "edges"]),
+            Err(ConfigError::SecretsUnused)
+        );
+    }
+
+    #[test]
+    fn counts_start_at_one() {
+        assert_eq!(build(&["bot", "--games", "1"]).unwrap().games, 1);
+        for flag in ["--games", "--rounds", "--max-attempts", "--fuel"] {
+            assert_eq!(
+                build(&["bot", flag, "0"]),
+                Err(ConfigError::AtLeastOne(flag))
+            );
+        }
+        assert_eq!(build(&["--time-limit", "0"]), Err(ConfigError::NoTime));
+    }
+
+    #[test]
+    fn bad_arguments_are_explained() {
+        let cases = [
+            (vec!["--frobnicate"], "unknown argument `--frobnicate`"),
+            (vec!["--seed"], "`--seed` needs a value"),
+            (vec!["--seed", "soon"], "invalid value `soon` for `--seed`"),
+            (
+                vec!["--min", "10", "--max", "5"],
+                "`--min` (10) can't be larger than `--max` (5)",
+            ),
+            (
+                vec!["tournament", "--bot", "a.wasm"],
+                "a tournament needs at least two `--bot`s",
+            ),
+            (
+                vec!["--lang", "fr"],
+                "unsupported language `fr` (expected en, nl)",
+            ),
+            (
+                vec!["stats", "--from", "2024-05-02", "--to", "2024-05-01"],
+                "`--from` (2024-05-02) can't be after `--to` (2024-05-01)",
+            ),
+        ];
+        for (args, message) in cases {
+            assert_eq!(build(&args).unwrap_err().to_string(), message);
+        }
+    }
+}
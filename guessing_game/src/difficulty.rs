@@ -0,0 +1,67 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+// ========== Difficulty presets ==========
+/*
+The original game always used 1..=100. The presets give names to the common ranges,
+and Custom covers everything set with `--min`/`--max` (including negative and full 64-bit ranges).
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Custom,
+}
+
+impl Difficulty {
+    // The range the secret is drawn from. Custom has no range of its own, it falls back to Normal's.
+    pub fn range(&self) -> RangeInclusive<i64> {
+        match self {
+            Difficulty::Easy => 1..=10,
+            Difficulty::Normal | Difficulty::Custom => 1..=100,
+            Difficulty::Hard => 1..=10_000,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+            Difficulty::Custom => "custom",
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Difficulty, String> {
+        match s.to_lowercase().as_str() {
+            "easy" => Ok(Difficulty::Easy),
+            "normal" => Ok(Difficulty::Normal),
+            "hard" => Ok(Difficulty::Hard),
+            "custom" => Ok(Difficulty::Custom),
+            _ => Err(format!(
+                "unknown difficulty `{s}` (expected easy, normal, hard or custom)"
+            )),
+        }
+    }
+}
+
+// How many numbers are in the range. i64::MIN..=i64::MAX holds 2^64 numbers, which doesn't fit in a u64.
+pub fn range_size(range: &RangeInclusive<i64>) -> u128 {
+    (*range.end() as i128 - *range.start() as i128 + 1).max(0) as u128
+}
@@ -0,0 +1,527 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+use crate::difficulty::{self, Difficulty};
+use crate::hints::{Clue, Hint, HintError};
+use crate::host::{Host, Secret};
+use crate::i18n;
+use crate::score;
+use crate::secrets::SecretSource;
+
+// ========== Outcome ==========
+/*
+A game is either still running, has been won, or has been lost by running out of attempts.
+The finished variants carry the number of attempts it took, so front-ends don't have to count themselves.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    InProgress,
+    Won { attempts: u32 },
+    Lost { attempts: u32 },
+}
+
+// ========== Errors ==========
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuessError {
+    // The game was already won or lost; further guesses are not counted.
+    Finished,
+    // The guess can't be the secret, so it's rejected instead of compared (and not counted as an attempt).
+    OutOfRange { guess: i64, min: i64, max: i64 },
+    // The guess came in after the time limit; the game is lost instead.
+    TimeUp,
+}
+
+impl fmt::Display for GuessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            GuessError::Finished => i18n::text("game-finished"),
+            GuessError::TimeUp => i18n::text("guess-time-up"),
+            GuessError::OutOfRange { guess, min, max } => i18n::message(
+                "out-of-range",
+                &[("guess", guess), ("min", min), ("max", max)],
+            ),
+        };
+        f.write_str(&text)
+    }
+}
+
+impl Error for GuessError {}
+
+// ========== Game ==========
+/*
+The state machine itself. It owns the secret, the range it was drawn from and the history of guesses.
+Every call to `guess` compares the guess to the secret and returns the `Ordering`
+(the secret may be chosen lazily by an evil host, see host.rs):
+`Less` means the guess was too small, `Greater` too big and `Equal` wins the game.
+With a maximum number of attempts, the wrong guess that uses up the last attempt loses the game.
+
+The clock starts when the game is created and stops when it is finished; the score uses it.
+With a time limit the game is lost once the clock passes it. The engine can't interrupt a player
+who is still typing, so the front-end calls `check_time` when its own timer runs out; a guess that
+comes in late is refused as well.
+ */
+#[derive(Debug, Clone)]
+pub struct Game {
+    secret: Secret,
+    range: RangeInclusive<i64>,
+    max_attempts: Option<u32>,
+    time_limit: Option<Duration>,
+    history: Vec<(i64, Ordering)>,
+    // Every hint given, with its name and what it cost (in percent of the score).
+    hints: Vec<(String, Clue, u32)>,
+    outcome: Outcome,
+    clock: Clock,
+    finished_after: Option<Duration>,
+}
+
+// The game's clock: running since an instant, or stopped at a fixed time so that what's shown of
+// it doesn't depend on how fast the machine is (see `with_stopped_clock`).
+#[derive(Debug, Clone, Copy)]
+enum Clock {
+    Running(Instant),
+    Stopped(Duration),
+}
+
+impl Clock {
+    fn elapsed(self) -> Duration {
+        match self {
+            Clock::Running(started) => started.elapsed(),
+            Clock::Stopped(elapsed) => elapsed,
+        }
+    }
+}
+
+impl Game {
+    // The secret has to lie within the range, otherwise the game could never be won.
+    pub fn new(range: RangeInclusive<i64>, secret: i64) -> Game {
+        assert!(range.contains(&secret), "secret must be within the range");
+        Game::with_secret(range, Secret::Fixed(secret))
+    }
+
+    pub(crate) fn with_secret(range: RangeInclusive<i64>, secret: Secret) -> Game {
+        Game {
+            secret,
+            range,
+            max_attempts: None,
+            time_limit: None,
+            history: Vec::new(),
+            hints: Vec::new(),
+            outcome: Outcome::InProgress,
+            clock: Clock::Running(Instant::now()),
+            finished_after: None,
+        }
+    }
+
+    // Limit the number of attempts; None (the default) lets the player guess until they win.
+    pub fn with_max_attempts(mut self, max_attempts: Option<u32>) -> Game {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    // Limit the time to play; None (the default) lets the player take as long as they like.
+    pub fn with_time_limit(mut self, time_limit: Option<Duration>) -> Game {
+        self.time_limit = time_limit;
+        self
+    }
+
+    // Continue the clock of a game that already ran for `elapsed`, e.g. one that was saved and resumed.
+    pub fn with_elapsed(mut self, elapsed: Duration) -> Game {
+        self.clock = match self.clock {
+            Clock::Running(started) => {
+                Clock::Running(Instant::now().checked_sub(elapsed).unwrap_or(started))
+            }
+            Clock::Stopped(_) => Clock::Stopped(elapsed),
+        };
+        self
+    }
+
+    // Stop the clock at `elapsed`: it only moves again with `with_elapsed`. For snapshots of a
+    // game, and tests of anything that shows the time.
+    pub fn with_stopped_clock(mut self, elapsed: Duration) -> Game {
+        self.clock = Clock::Stopped(elapsed);
+        self
+    }
+
+    // Same as the original program: a secret between 1 and 100 (inclusive), seeded by the OS.
+    pub fn random() -> Game {
+        Game::from_rng(Difficulty::Normal.range(), &mut rand::thread_rng())
+    }
+
+    // Draw the secret from any generator; pass a seeded `GameRng` to get the same secret every time.
+    pub fn from_rng<R: Rng>(range: RangeInclusive<i64>, rng: &mut R) -> Game {
+        Game::hosted(range, Host::Fair, rng)
+    }
+
+    // Let `host` pick (or, for an evil host, not pick) the secret.
+    pub fn hosted<R: Rng>(range: RangeInclusive<i64>, host: Host, rng: &mut R) -> Game {
+        let secret = host.secret(&range, rng);
+        Game::with_secret(range, secret)
+    }
+
+    // Same, with a fair host drawing from `source` instead of uniformly.
+    pub fn sourced<R: Rng>(
+        range: RangeInclusive<i64>,
+        host: Host,
+        source: &mut dyn SecretSource,
+        rng: &mut R,
+    ) -> Game {
+        let secret = host.secret_from(&range, source, rng);
+        if let Secret::Fixed(secret) = secret {
+            assert!(
+                range.contains(&secret),
+                "the `{}` source drew a secret outside the range",
+                source.name()
+            );
+        }
+        Game::with_secret(range, secret)
+    }
+
+    pub fn guess(&mut self, guess: i64) -> Result<Ordering, GuessError> {
+        if self.is_finished() {
+            return Err(GuessError::Finished);
+        }
+        self.check_time();
+        if self.timed_out() {
+            return Err(GuessError::TimeUp);
+        }
+        if !self.range.contains(&guess) {
+            return Err(GuessError::OutOfRange {
+                guess,
+                min: *self.range.start(),
+                max: *self.range.end(),
+            });
+        }
+
+        let ordering = self.secret.answer(guess);
+        self.history.push((guess, ordering));
+
+        let attempts = self.attempts();
+        if ordering == Ordering::Equal {
+            self.finish(Outcome::Won { attempts });
+        } else if self.max_attempts.is_some_and(|max| attempts >= max) {
+            self.finish(Outcome::Lost { attempts });
+        }
+
+        Ok(ordering)
+    }
+
+    fn finish(&mut self, outcome: Outcome) {
+        self.outcome = outcome;
+        self.finished_after = Some(self.clock.elapsed());
+    }
+
+    // Ends the game as lost when the time limit has passed. The clock stops at the limit.
+    pub fn check_time(&mut self) {
+        if let (Some(limit), Some(Duration::ZERO)) = (self.time_limit, self.time_left()) {
+            if !self.is_finished() {
+                self.finish(Outcome::Lost {
+                    attempts: self.attempts(),
+                });
+                self.finished_after = Some(limit);
+            }
+        }
+    }
+
+    // Whether the game was lost on time rather than on attempts.
+    pub fn timed_out(&self) -> bool {
+        matches!(self.outcome, Outcome::Lost { .. })
+            && self.time_limit.is_some_and(|limit| self.elapsed() >= limit)
+    }
+
+    pub fn outcome(&self) -> Outcome {
+        self.outcome
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.history.len() as u32
+    }
+
+    pub fn max_attempts(&self) -> Option<u32> {
+        self.max_attempts
+    }
+
+    // None when there is no limit.
+    pub fn attempts_left(&self) -> Option<u32> {
+        self.max_attempts
+            .map(|max| max.saturating_sub(self.attempts()))
+    }
+
+    pub fn time_limit(&self) -> Option<Duration> {
+        self.time_limit
+    }
+
+    // None when there is no limit.
+    pub fn time_left(&self) -> Option<Duration> {
+        self.time_limit
+            .map(|limit| limit.saturating_sub(self.elapsed()))
+    }
+
+    // Time since the game started, frozen once it's finished.
+    pub fn elapsed(&self) -> Duration {
+        self.finished_after.unwrap_or_else(|| self.clock.elapsed())
+    }
+
+    // Only a won game has a score; None while playing or after a loss.
+    // Every hint takes its cost (a percentage) off the score, down to 0 at most.
+    pub fn score(&self) -> Option<u32> {
+        match self.outcome {
+            Outcome::Won { attempts } => {
+                let score = score::score(
+                    attempts,
+                    difficulty::range_size(&self.range),
+                    self.elapsed(),
+                );
+                let kept = 100u32.saturating_sub(self.hint_cost());
+                Some((score as u64 * kept as u64 / 100) as u32)
+            }
+            _ => None,
+        }
+    }
+
+    /*
+    Asks a hint rule for a clue about the secret. The clue is checked against the secret before it's
+    given out (and recorded, so it counts towards the score): a rule that gets it wrong is an error.
+     */
+    pub fn hint(&mut self, hint: &dyn Hint) -> Result<Clue, HintError> {
+        if self.is_finished() {
+            return Err(HintError::Finished);
+        }
+        let Secret::Fixed(secret) = self.secret else {
+            return Err(HintError::NoSecret);
+        };
+
+        let clue = hint
+            .clue(secret, self)
+            .ok_or_else(|| HintError::NotApplicable(hint.name().to_string()))?;
+        if !clue.holds_for(secret) {
+            return Err(HintError::Contradiction {
+                hint: hint.name().to_string(),
+                clue,
+            });
+        }
+
+        self.hints
+            .push((hint.name().to_string(), clue, hint.cost()));
+        Ok(clue)
+    }
+
+    pub fn hints(&self) -> &[(String, Clue, u32)] {
+        &self.hints
+    }
+
+    // The total cost of all hints so far, in percent of the score.
+    pub fn hint_cost(&self) -> u32 {
+        self.hints.iter().map(|(_, _, cost)| cost).sum()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.outcome != Outcome::InProgress
+    }
+
+    pub fn history(&self) -> &[(i64, Ordering)] {
+        &self.history
+    }
+
+    // The secret, or for an evil host a number that agrees with every answer it gave.
+    pub fn secret(&self) -> i64 {
+        self.secret.reveal()
+    }
+
+    pub fn host(&self) -> Host {
+        match self.secret {
+            Secret::Fixed(_) => Host::Fair,
+            Secret::Lazy { .. } => Host::Evil,
+        }
+    }
+
+    pub fn range(&self) -> &RangeInclusive<i64> {
+        &self.range
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::hints::{self, Clue, HintError};
+
+    #[test]
+    fn guesses_are_compared_to_the_secret() {
+        let mut game = Game::new(1..=100, 42);
+        assert_eq!(game.guess(10), Ok(Ordering::Less));
+        assert_eq!(game.guess(90), Ok(Ordering::Greater));
+        assert_eq!(game.outcome(), Outcome::InProgress);
+        assert_eq!(game.guess(42), Ok(Ordering::Equal));
+        assert_eq!(game.outcome(), Outcome::Won { attempts: 3 });
+        assert_eq!(
+            game.history(),
+            [
+                (10, Ordering::Less),
+                (90, Ordering::Greater),
+                (42, Ordering::Equal)
+            ]
+        );
+        assert!(game.score().is_some());
+    }
+
+    #[test]
+    fn guesses_outside_the_range_are_not_counted() {
+        let mut game = Game::new(1..=100, 42);
+        assert_eq!(
+            game.guess(101),
+            Err(GuessError::OutOfRange {
+                guess: 101,
+                min: 1,
+                max: 100
+            })
+        );
+        assert_eq!(game.attempts(), 0);
+    }
+
+    #[test]
+    fn a_finished_game_takes_no_more_guesses() {
+        let mut game = Game::new(1..=100, 42);
+        game.guess(42).unwrap();
+        assert_eq!(game.guess(42), Err(GuessError::Finished));
+        assert_eq!(game.attempts(), 1);
+    }
+
+    #[test]
+    fn the_last_attempt_loses_the_game() {
+        let mut game = Game::new(1..=100, 42).with_max_attempts(Some(2));
+        assert_eq!(game.attempts_left(), Some(2));
+        game.guess(1).unwrap();
+        assert_eq!(game.attempts_left(), Some(1));
+        game.guess(2).unwrap();
+        assert_eq!(game.outcome(), Outcome::Lost { attempts: 2 });
+        assert_eq!(game.attempts_left(), Some(0));
+        assert_eq!(game.score(), None);
+        assert_eq!(game.guess(42), Err(GuessError::Finished));
+    }
+
+    #[test]
+    fn winning_on_the_last_attempt_still_wins() {
+        let mut game = Game::new(1..=100, 42).with_max_attempts(Some(1));
+        game.guess(42).unwrap();
+        assert_eq!(game.outcome(), Outcome::Won { attempts: 1 });
+    }
+
+    #[test]
+    fn the_evil_host_keeps_the_larger_half() {
+        let mut game = Game::hosted(1..=100, Host::Evil, &mut rand::thread_rng());
+        assert_eq!(game.host(), Host::Evil);
+        // 1..=49 below and 51..=100 above: the upper half is bigger.
+        assert_eq!(game.guess(50), Ok(Ordering::Less));
+        // 51..=74 below and 76..=100 above.
+        assert_eq!(game.guess(75), Ok(Ordering::Less));
+        // Bisecting what's left only wins once a single number remains.
+        let mut attempts = 2;
+        let (mut low, mut high) = (76, 100);
+        loop {
+            let guess = low + (high - low) / 2;
+            attempts += 1;
+            match game.guess(guess).unwrap() {
+                Ordering::Less => low = guess + 1,
+                Ordering::Greater => high = guess - 1,
+                Ordering::Equal => break,
+            }
+        }
+        assert_eq!(low, high);
+        assert_eq!(game.outcome(), Outcome::Won { attempts });
+        assert_eq!(game.secret(), low);
+    }
+
+    #[test]
+    fn the_evil_host_answers_the_full_i64_range() {
+        let mut game = Game::hosted(i64::MIN..=i64::MAX, Host::Evil, &mut rand::thread_rng());
+        // 2^63 numbers below 0, one fewer above it.
+        assert_eq!(game.guess(0), Ok(Ordering::Greater));
+        assert_eq!(game.guess(i64::MIN), Ok(Ordering::Less));
+        assert_eq!(game.guess(-1), Ok(Ordering::Greater));
+    }
+
+    #[test]
+    fn hints_are_true_and_cost_score() {
+        let mut game = Game::new(1..=100, 42);
+        let parity = hints::hint("parity").unwrap();
+        assert_eq!(game.hint(parity.as_ref()), Ok(Clue::Even(true)));
+        game.guess(50).unwrap();
+        let interval = hints::hint("interval").unwrap();
+        assert_eq!(
+            game.hint(interval.as_ref()),
+            Ok(Clue::Between { low: 1, high: 49 })
+        );
+        assert_eq!(game.hint_cost(), 25);
+
+        let mut plain = Game::new(1..=100, 42);
+        plain.guess(50).unwrap();
+        plain.guess(42).unwrap();
+        game.guess(42).unwrap();
+        let (with, without) = (game.score().unwrap(), plain.score().unwrap());
+        assert!(with < without, "{with} should be less than {without}");
+    }
+
+    #[test]
+    fn hints_need_a_secret_and_a_running_game() {
+        let distance = hints::hint("distance").unwrap();
+        let mut game = Game::new(1..=100, 42);
+        assert_eq!(
+            game.hint(distance.as_ref()),
+            Err(HintError::NotApplicable("distance".to_string()))
+        );
+        game.guess(42).unwrap();
+        assert_eq!(game.hint(distance.as_ref()), Err(HintError::Finished));
+
+        let mut evil = Game::hosted(1..=100, Host::Evil, &mut rand::thread_rng());
+        assert_eq!(evil.hint(distance.as_ref()), Err(HintError::NoSecret));
+    }
+
+    #[test]
+    fn a_stopped_clock_stays_put() {
+        let second = Duration::from_secs(1);
+        let mut game = Game::new(1..=100, 42)
+            .with_time_limit(Some(Duration::from_secs(60)))
+            .with_stopped_clock(Duration::from_secs(42));
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(game.elapsed(), 42 * second);
+        assert_eq!(game.time_left(), Some(18 * second));
+
+        game = game.with_elapsed(59 * second);
+        game.guess(42).unwrap();
+        assert_eq!(game.elapsed(), 59 * second);
+        assert_eq!(game.outcome(), Outcome::Won { attempts: 1 });
+    }
+
+    #[test]
+    fn the_time_limit_loses_the_game() {
+        let limit = Duration::from_secs(10);
+        let mut game = Game::new(1..=100, 42)
+            .with_time_limit(Some(limit))
+            .with_elapsed(Duration::from_secs(5));
+        game.guess(1).unwrap();
+        assert!(!game.timed_out());
+
+        let mut game = game.with_elapsed(Duration::from_secs(11));
+        assert_eq!(game.guess(42), Err(GuessError::TimeUp));
+        assert_eq!(game.outcome(), Outcome::Lost { attempts: 1 });
+        assert!(game.timed_out());
+        // The clock stops at the limit.
+        assert_eq!(game.elapsed(), limit);
+        assert_eq!(game.time_left(), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn check_time_ends_a_game_without_a_guess() {
+        let mut game = Game::new(1..=100, 42)
+            .with_time_limit(Some(Duration::from_secs(1)))
+            .with_elapsed(Duration::from_secs(2));
+        game.check_time();
+        assert_eq!(game.outcome(), Outcome::Lost { attempts: 0 });
+    }
+}
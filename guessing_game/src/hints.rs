@@ -0,0 +1,229 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::bot::Bounds;
+use crate::game::Game;
+use crate::i18n;
+
+// ========== Hints ==========
+/*
+Besides "too small" and "too big", the player can ask for a hint with `:hint <name>`.
+Every hint costs a percentage of the score (see `Game::score`).
+
+Each kind of hint is a rule implementing `Hint`: given the secret (and the game so far) it produces
+a `Clue`. New rules only need to be added to `hints()`.
+A clue is a statement about the secret that can be checked with `holds_for`, and the engine does
+exactly that before handing it out (see `Game::hint`): a buggy rule can't mislead the player.
+ */
+pub trait Hint {
+    // What the player types after `:hint`.
+    fn name(&self) -> &str;
+
+    // Percentage of the score this hint costs.
+    fn cost(&self) -> u32;
+
+    // None when the hint doesn't apply (yet), e.g. a distance without any guess to measure from.
+    fn clue(&self, secret: i64, game: &Game) -> Option<Clue>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Clue {
+    Even(bool),
+    DivisibleBy { divisor: i64, divisible: bool },
+    Between { low: i64, high: i64 },
+    // `within` is Some(n) when the secret is at most n away from `guess`, None when it's further
+    // away than the widest band.
+    Distance { guess: i64, within: Option<u64> },
+}
+
+impl Clue {
+    pub fn holds_for(&self, secret: i64) -> bool {
+        match *self {
+            Clue::Even(even) => (secret.rem_euclid(2) == 0) == even,
+            Clue::DivisibleBy { divisor, divisible } => {
+                (secret.rem_euclid(divisor) == 0) == divisible
+            }
+            Clue::Between { low, high } => (low..=high).contains(&secret),
+            Clue::Distance { guess, within } => {
+                let distance = guess.abs_diff(secret);
+                match within {
+                    Some(band) => distance <= band,
+                    None => distance > *DISTANCE_BANDS.last().expect("there are bands"),
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Clue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let furthest = *DISTANCE_BANDS.last().expect("there are bands");
+        let text = match self {
+            Clue::Even(true) => i18n::text("clue-even"),
+            Clue::Even(false) => i18n::text("clue-odd"),
+            Clue::DivisibleBy {
+                divisor,
+                divisible: true,
+            } => i18n::message("clue-divisible", &[("divisor", divisor)]),
+            Clue::DivisibleBy {
+                divisor,
+                divisible: false,
+            } => i18n::message("clue-not-divisible", &[("divisor", divisor)]),
+            Clue::Between { low, high } => {
+                i18n::message("clue-between", &[("low", low), ("high", high)])
+            }
+            Clue::Distance {
+                guess,
+                within: Some(band),
+            } => i18n::message("clue-within", &[("band", band), ("guess", guess)]),
+            Clue::Distance {
+                guess,
+                within: None,
+            } => i18n::message("clue-further", &[("band", &furthest), ("guess", guess)]),
+        };
+        f.write_str(&text)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HintError {
+    Unknown(String),
+    // The hint with this name doesn't apply right now.
+    NotApplicable(String),
+    // An evil host hasn't picked a secret, so there's nothing to give hints about.
+    NoSecret,
+    Finished,
+    // The rule came up with a clue that isn't true for the secret. That's a bug in the rule.
+    Contradiction { hint: String, clue: Clue },
+}
+
+impl fmt::Display for HintError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            HintError::Unknown(name) => i18n::message("hint-unknown", &[("name", name)]),
+            HintError::NotApplicable(name) => {
+                i18n::message("hint-not-applicable", &[("name", name)])
+            }
+            HintError::NoSecret => i18n::text("hint-no-secret"),
+            HintError::Finished => i18n::text("game-finished"),
+            HintError::Contradiction { hint, clue } => {
+                i18n::message("hint-contradiction", &[("name", hint), ("clue", clue)])
+            }
+        };
+        f.write_str(&text)
+    }
+}
+
+impl Error for HintError {}
+
+// ========== Rules ==========
+pub struct Parity;
+
+impl Hint for Parity {
+    fn name(&self) -> &str {
+        "parity"
+    }
+
+    fn cost(&self) -> u32 {
+        10
+    }
+
+    fn clue(&self, secret: i64, _game: &Game) -> Option<Clue> {
+        Some(Clue::Even(secret.rem_euclid(2) == 0))
+    }
+}
+
+// Divisibility by a small prime: `div3`, `div5` and `div7`.
+pub struct Divisibility {
+    name: String,
+    prime: i64,
+}
+
+impl Divisibility {
+    pub fn new(prime: i64) -> Divisibility {
+        Divisibility {
+            name: format!("div{prime}"),
+            prime,
+        }
+    }
+}
+
+impl Hint for Divisibility {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn cost(&self) -> u32 {
+        10
+    }
+
+    fn clue(&self, secret: i64, _game: &Game) -> Option<Clue> {
+        Some(Clue::DivisibleBy {
+            divisor: self.prime,
+            divisible: secret.rem_euclid(self.prime) == 0,
+        })
+    }
+}
+
+// What's left of the range after all the answers so far; handy after a long game.
+pub struct Interval;
+
+impl Hint for Interval {
+    fn name(&self) -> &str {
+        "interval"
+    }
+
+    fn cost(&self) -> u32 {
+        15
+    }
+
+    fn clue(&self, _secret: i64, game: &Game) -> Option<Clue> {
+        let mut bounds = Bounds::new(game.range());
+        for &(guess, ordering) in game.history() {
+            bounds.update(guess, ordering);
+        }
+        Some(Clue::Between {
+            low: bounds.low,
+            high: bounds.high,
+        })
+    }
+}
+
+// How far the secret is from the last guess: within 10, within 50, or further.
+pub const DISTANCE_BANDS: [u64; 2] = [10, 50];
+
+pub struct Distance;
+
+impl Hint for Distance {
+    fn name(&self) -> &str {
+        "distance"
+    }
+
+    fn cost(&self) -> u32 {
+        20
+    }
+
+    fn clue(&self, secret: i64, game: &Game) -> Option<Clue> {
+        let &(guess, _) = game.history().last()?;
+        let distance = guess.abs_diff(secret);
+        Some(Clue::Distance {
+            guess,
+            within: DISTANCE_BANDS.into_iter().find(|band| distance <= *band),
+        })
+    }
+}
+
+pub fn hints() -> Vec<Box<dyn Hint>> {
+    vec![
+        Box::new(Parity),
+        Box::new(Divisibility::new(3)),
+        Box::new(Divisibility::new(5)),
+        Box::new(Divisibility::new(7)),
+        Box::new(Interval),
+        Box::new(Distance),
+    ]
+}
+
+pub fn hint(name: &str) -> Option<Box<dyn Hint>> {
+    hints().into_iter().find(|hint| hint.name() == name)
+}
@@ -0,0 +1,46 @@
+use std::collections::BTreeMap;
+
+// ========== ASCII histograms ==========
+/*
+Renders counts per number of attempts as horizontal bars, like:
+
+     5 | ########                                  12
+     6 | ########################################  61
+
+When the values are spread out (a linear search on 1..=10_000 takes anywhere between 1 and 10_000
+attempts) neighbouring values are grouped, so the histogram never gets more than `MAX_ROWS` rows.
+ */
+const MAX_ROWS: u32 = 20;
+const BAR_WIDTH: u32 = 40;
+
+pub fn render(counts: &BTreeMap<u32, u32>) -> String {
+    let (Some(&first), Some(&last)) = (counts.keys().next(), counts.keys().next_back()) else {
+        return String::new();
+    };
+
+    // Group the values into rows of `bucket` values each.
+    let bucket = (last - first) / MAX_ROWS + 1;
+    let mut rows: BTreeMap<u32, u32> = BTreeMap::new();
+    for (&value, &count) in counts {
+        *rows.entry((value - first) / bucket).or_insert(0) += count;
+    }
+    let tallest = rows.values().copied().max().unwrap_or(1);
+
+    let mut histogram = String::new();
+    for row in 0..=(last - first) / bucket {
+        let count = rows.get(&row).copied().unwrap_or(0);
+        let start = first + row * bucket;
+        let label = if bucket == 1 {
+            format!("{start}")
+        } else {
+            format!("{start}-{}", start + bucket - 1)
+        };
+        // Round up, so a row with only a few games still shows a bar.
+        let bar = "#".repeat((count as u64 * BAR_WIDTH as u64).div_ceil(tallest as u64) as usize);
+        histogram.push_str(&format!(
+            "{label:>11} | {bar:<width$} {count}\n",
+            width = BAR_WIDTH as usize
+        ));
+    }
+    histogram
+}
@@ -0,0 +1,126 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use rand::Rng;
+
+use crate::secrets::{SecretSource, Uniform};
+
+// ========== Hosts ==========
+/*
+The host is the side of the game that knows the secret and answers the guesses.
+
+- A fair host picks the secret up front, like the original game did.
+- An evil host never commits to a secret. It only keeps track of the numbers that are still
+  consistent with its earlier answers (always an interval, because all it ever says is higher/lower),
+  and answers every guess so that the largest part of that interval survives.
+  Only when a single number is left can a guess be "correct".
+
+Against the evil host every strategy gets its worst case: binary search needs the full
+ceil(log2(range size)) attempts every single game, and a lucky guess is impossible.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Host {
+    #[default]
+    Fair,
+    Evil,
+}
+
+impl Host {
+    pub fn secret<R: Rng>(&self, range: &RangeInclusive<i64>, rng: &mut R) -> Secret {
+        self.secret_from(range, &mut Uniform, rng)
+    }
+
+    // A fair host draws the secret from `source`, see secrets.rs.
+    pub fn secret_from<R: Rng>(
+        &self,
+        range: &RangeInclusive<i64>,
+        source: &mut dyn SecretSource,
+        rng: &mut R,
+    ) -> Secret {
+        match self {
+            Host::Fair => Secret::Fixed(source.draw(range, rng)),
+            Host::Evil => Secret::Lazy {
+                low: *range.start(),
+                high: *range.end(),
+            },
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Host::Fair => "fair",
+            Host::Evil => "evil",
+        }
+    }
+}
+
+impl fmt::Display for Host {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+impl FromStr for Host {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Host, String> {
+        match s.to_lowercase().as_str() {
+            "fair" => Ok(Host::Fair),
+            "evil" => Ok(Host::Evil),
+            _ => Err(format!("unknown host `{s}` (expected fair or evil)")),
+        }
+    }
+}
+
+// ========== Secret ==========
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Secret {
+    // Chosen up front by a fair host.
+    Fixed(i64),
+    // Chosen lazily by an evil host: any number from low to high is still possible.
+    Lazy { low: i64, high: i64 },
+}
+
+impl Secret {
+    // The guess compared to the secret, the same way `guess.cmp(&secret)` would.
+    pub fn answer(&mut self, guess: i64) -> Ordering {
+        match self {
+            Secret::Fixed(secret) => guess.cmp(secret),
+            Secret::Lazy { low, high } => {
+                if guess < *low {
+                    return Ordering::Less;
+                }
+                if guess > *high {
+                    return Ordering::Greater;
+                }
+
+                // Count the numbers left on either side of the guess (in i128, the full i64 range
+                // doesn't fit in an i64) and keep the bigger side. Only when both are empty is the guess right.
+                let above = *high as i128 - guess as i128;
+                let below = guess as i128 - *low as i128;
+                if above == 0 && below == 0 {
+                    Ordering::Equal
+                } else if above >= below {
+                    *low = guess + 1;
+                    Ordering::Less
+                } else {
+                    *high = guess - 1;
+                    Ordering::Greater
+                }
+            }
+        }
+    }
+
+    /*
+    A number consistent with every answer so far. For a fixed secret that's simply the secret;
+    an evil host hasn't picked one, so it reveals the lowest number it could still claim.
+     */
+    pub fn reveal(&self) -> i64 {
+        match self {
+            Secret::Fixed(secret) => *secret,
+            Secret::Lazy { low, .. } => *low,
+        }
+    }
+}
@@ -0,0 +1,588 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use rand::RngCore;
+
+use crate::game::Outcome;
+use crate::i18n;
+use crate::input::{self, InputError};
+use crate::secrets::SecretSource;
+
+// ========== Hot and cold ==========
+/*
+The third game mode. Guesses aren't compared with the secret, but with each other: every guess is
+answered with warmer (closer to the secret than the guess before it), colder (further away), or
+neither (just as far). The first guess has nothing to be compared with, so it only starts the game.
+
+The secret is a number in the range, or with `--grid` a point on a square grid with the range on
+both axes, guessed as `x,y`. Distances on the grid are Euclidean, so warmer means inside the circle
+around the secret that goes through the previous guess. Halving the range doesn't work here: the
+answers say which of two guesses is closer, so a solver has to pick pairs that split what's left
+(see `Solver`).
+
+Distances are compared squared, in integers, so two guesses at the same distance are never told
+apart by rounding. That works on the whole i64 range too, see `DistanceSquared`.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Board {
+    Line,
+    Grid,
+}
+
+impl Board {
+    pub fn dimensions(self) -> usize {
+        match self {
+            Board::Line => 1,
+            Board::Grid => 2,
+        }
+    }
+}
+
+// On a line there is no `y`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Point {
+    pub x: i64,
+    pub y: Option<i64>,
+}
+
+// A squared distance, exactly: each square fits in a u128, but on a grid of the whole i64 range
+// their sum doesn't, so whatever goes past u128::MAX is kept in `carry`. The fields are in the
+// order they compare in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DistanceSquared {
+    carry: bool,
+    low: u128,
+}
+
+impl DistanceSquared {
+    pub const ZERO: DistanceSquared = DistanceSquared {
+        carry: false,
+        low: 0,
+    };
+
+    pub fn to_f64(self) -> f64 {
+        let carry = if self.carry { 2f64.powi(128) } else { 0.0 };
+        carry + self.low as f64
+    }
+}
+
+impl Point {
+    pub fn distance_squared(self, other: Point) -> DistanceSquared {
+        let dx = self.x.abs_diff(other.x) as u128;
+        let dy = self.y.unwrap_or(0).abs_diff(other.y.unwrap_or(0)) as u128;
+        let (low, carry) = (dx * dx).overflowing_add(dy * dy);
+        DistanceSquared { carry, low }
+    }
+
+    pub fn distance(self, other: Point) -> f64 {
+        self.distance_squared(other).to_f64().sqrt()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Temperature {
+    // The first guess: nothing to compare it with yet.
+    First,
+    Warmer,
+    Colder,
+    // Just as far from the secret as the guess before.
+    Same,
+    Found,
+}
+
+impl fmt::Display for Temperature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let id = match self {
+            Temperature::First => "hot-first",
+            Temperature::Warmer => "hot-warmer",
+            Temperature::Colder => "hot-colder",
+            Temperature::Same => "hot-same",
+            Temperature::Found => "hot-found",
+        };
+        f.write_str(&i18n::text(id))
+    }
+}
+
+#[derive(Debug)]
+pub enum PointError {
+    // The game was already won or lost.
+    Finished,
+    // A grid point is two numbers, like `3,4`; a line only has one.
+    Coordinates { expected: usize, got: usize },
+    // One of the coordinates isn't a number within the range.
+    Input(InputError),
+}
+
+impl fmt::Display for PointError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PointError::Finished => f.write_str(&i18n::text("game-finished")),
+            PointError::Coordinates { expected, got } => f.write_str(&i18n::message(
+                "hot-coordinates",
+                &[("expected", expected), ("got", got)],
+            )),
+            PointError::Input(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for PointError {}
+
+impl From<InputError> for PointError {
+    fn from(err: InputError) -> PointError {
+        PointError::Input(err)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HotCold {
+    board: Board,
+    range: RangeInclusive<i64>,
+    secret: Point,
+    max_attempts: Option<u32>,
+    history: Vec<(Point, Temperature)>,
+    outcome: Outcome,
+}
+
+impl HotCold {
+    pub fn new(board: Board, range: RangeInclusive<i64>, secret: Point) -> HotCold {
+        let game = HotCold {
+            board,
+            range,
+            secret,
+            max_attempts: None,
+            history: Vec::new(),
+            outcome: Outcome::InProgress,
+        };
+        assert!(
+            game.check(secret).is_ok(),
+            "the secret has to be on the board"
+        );
+        game
+    }
+
+    // Every coordinate of the secret is drawn from `source`, so `--secrets` works here too.
+    pub fn sourced(
+        board: Board,
+        range: RangeInclusive<i64>,
+        source: &mut dyn SecretSource,
+        rng: &mut dyn RngCore,
+    ) -> HotCold {
+        let x = source.draw(&range, rng);
+        let y = match board {
+            Board::Line => None,
+            Board::Grid => Some(source.draw(&range, rng)),
+        };
+        HotCold::new(board, range, Point { x, y })
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: Option<u32>) -> HotCold {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    pub fn guess(&mut self, guess: Point) -> Result<Temperature, PointError> {
+        if self.is_finished() {
+            return Err(PointError::Finished);
+        }
+        self.check(guess)?;
+
+        let distance = guess.distance_squared(self.secret);
+        let temperature = match self.history.last() {
+            _ if distance == DistanceSquared::ZERO => Temperature::Found,
+            None => Temperature::First,
+            Some((previous, _)) => {
+                let before = previous.distance_squared(self.secret);
+                match distance.cmp(&before) {
+                    Ordering::Less => Temperature::Warmer,
+                    Ordering::Greater => Temperature::Colder,
+                    Ordering::Equal => Temperature::Same,
+                }
+            }
+        };
+        self.history.push((guess, temperature));
+
+        let attempts = self.attempts();
+        if temperature == Temperature::Found {
+            self.outcome = Outcome::Won { attempts };
+        } else if self.max_attempts.is_some_and(|max| attempts >= max) {
+            self.outcome = Outcome::Lost { attempts };
+        }
+
+        Ok(temperature)
+    }
+
+    // A point from a line like `42`, or `3,4` (or `3 4`) on a grid.
+    pub fn parse(&self, line: &str) -> Result<Point, PointError> {
+        let parts: Vec<&str> = line
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|part| !part.is_empty())
+            .collect();
+        let expected = self.board.dimensions();
+        if parts.len() != expected {
+            // An empty line gets the usual "please type a number".
+            if parts.is_empty() {
+                return Err(InputError::NotANumber(String::new()).into());
+            }
+            return Err(PointError::Coordinates {
+                expected,
+                got: parts.len(),
+            });
+        }
+
+        let x = input::parse_guess(parts[0], &self.range)?;
+        let y = match self.board {
+            Board::Line => None,
+            Board::Grid => Some(input::parse_guess(parts[1], &self.range)?),
+        };
+        Ok(Point { x, y })
+    }
+
+    fn check(&self, point: Point) -> Result<(), PointError> {
+        let coordinates: Vec<i64> = [Some(point.x), point.y].into_iter().flatten().collect();
+        if coordinates.len() != self.board.dimensions() {
+            return Err(PointError::Coordinates {
+                expected: self.board.dimensions(),
+                got: coordinates.len(),
+            });
+        }
+        for coordinate in coordinates {
+            if !self.range.contains(&coordinate) {
+                return Err(InputError::OutOfRange {
+                    guess: coordinate,
+                    min: *self.range.start(),
+                    max: *self.range.end(),
+                }
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn outcome(&self) -> Outcome {
+        self.outcome
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.history.len() as u32
+    }
+
+    pub fn attempts_left(&self) -> Option<u32> {
+        self.max_attempts
+            .map(|max| max.saturating_sub(self.attempts()))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.outcome != Outcome::InProgress
+    }
+
+    // Every guess with the answer it got, oldest first.
+    pub fn history(&self) -> &[(Point, Temperature)] {
+        &self.history
+    }
+
+    pub fn board(&self) -> Board {
+        self.board
+    }
+
+    pub fn range(&self) -> &RangeInclusive<i64> {
+        &self.range
+    }
+
+    pub fn secret(&self) -> Point {
+        self.secret
+    }
+}
+
+// ========== Solver ==========
+/*
+Plays hot and cold by itself. On a line it keeps the range the secret can still be in, and answers
+its previous guess `a` with its mirror image `b` in that range: warmer means the secret is on b's
+side of the point halfway between them, colder on a's side, and neither means it's right on that
+point. With `a` inside the range that halves it every guess. After a colder answer `a` is outside
+of it, and the mirror image may be off the board; then the guess lands on the edge of the board,
+which tells nothing but gets the next guess back inside. So it takes between log2(n) and
+2 log2(n) guesses.
+
+On a grid, two guesses that only differ in x are warmer or colder by their x alone (the y part of
+both distances is the same), so the solver finds x like on a line, and then y the same way.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Axis {
+    low: i64,
+    high: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Solver {
+    range: RangeInclusive<i64>,
+    axes: Vec<Axis>,
+    last: Option<Point>,
+}
+
+impl Solver {
+    pub fn new(board: Board, range: RangeInclusive<i64>) -> Solver {
+        let axis = Axis {
+            low: *range.start(),
+            high: *range.end(),
+        };
+        Solver {
+            axes: vec![axis; board.dimensions()],
+            range,
+            last: None,
+        }
+    }
+
+    pub fn next_guess(&self) -> Point {
+        let Some(axis) = self.searching() else {
+            return self.point(|axis| self.axes[axis].low);
+        };
+        let Axis { low, high } = self.axes[axis];
+        // The solved axes at their value, the ones after this one wherever the last guess was.
+        let mut guess = self.point(|other| match self.last {
+            Some(last) if other > axis => coordinate(last, other),
+            _ => self.axes[other].low,
+        });
+        let Some(last) = self
+            .last
+            .filter(|&last| self.only_differ_in(last, guess, axis))
+        else {
+            return guess;
+        };
+
+        let a = coordinate(last, axis);
+        let mirror = low as i128 + high as i128 - a as i128;
+        let start = *self.range.start() as i128;
+        let end = *self.range.end() as i128;
+        let mut b = mirror.clamp(start, end) as i64;
+        if b == a {
+            b = if a < high { a + 1 } else { a - 1 };
+        }
+        set_coordinate(&mut guess, axis, b);
+        guess
+    }
+
+    // Narrows down where the secret can be, with the answer to `guess`.
+    pub fn update(&mut self, guess: Point, temperature: Temperature) {
+        let last = self.last.replace(guess);
+        let Some(axis) = self.searching() else {
+            return;
+        };
+        if temperature == Temperature::Found {
+            for (index, axis) in self.axes.iter_mut().enumerate() {
+                let value = coordinate(guess, index);
+                *axis = Axis {
+                    low: value,
+                    high: value,
+                };
+            }
+            return;
+        }
+
+        let solved = (0..self.axes.len())
+            .filter(|&other| other != axis)
+            .all(|other| {
+                let Axis { low, high } = self.axes[other];
+                low == high && coordinate(guess, other) == low
+            });
+        let Axis { mut low, mut high } = self.axes[axis];
+        let b = coordinate(guess, axis);
+        // Not found, so with the other axes right this one is wrong.
+        if solved && b == low {
+            low += 1;
+        } else if solved && b == high {
+            high -= 1;
+        }
+
+        if let Some(last) = last.filter(|&last| self.only_differ_in(last, guess, axis)) {
+            let a = coordinate(last, axis);
+            // Twice the point halfway between the two guesses.
+            let sum = a as i128 + b as i128;
+            let above = (sum.div_euclid(2) + 1) as i64;
+            let below = (-(-sum).div_euclid(2) - 1) as i64;
+            let closer_to_b = match temperature {
+                Temperature::Warmer => Some(true),
+                Temperature::Colder => Some(false),
+                _ => None,
+            };
+            match closer_to_b {
+                Some(closer) if closer == (b > a) => low = low.max(above),
+                Some(_) => high = high.min(below),
+                None if sum % 2 == 0 => {
+                    low = (sum / 2) as i64;
+                    high = low;
+                }
+                // Halfway between two neighbours: the secret can't be there.
+                None => {}
+            }
+        }
+        self.axes[axis] = Axis { low, high };
+    }
+
+    // The first axis that isn't known yet, None once the secret is.
+    fn searching(&self) -> Option<usize> {
+        self.axes.iter().position(|axis| axis.low < axis.high)
+    }
+
+    fn only_differ_in(&self, a: Point, b: Point, axis: usize) -> bool {
+        (0..self.axes.len())
+            .all(|other| other == axis || coordinate(a, other) == coordinate(b, other))
+    }
+
+    fn point(&self, value: impl Fn(usize) -> i64) -> Point {
+        Point {
+            x: value(0),
+            y: (self.axes.len() > 1).then(|| value(1)),
+        }
+    }
+}
+
+fn coordinate(point: Point, axis: usize) -> i64 {
+    match axis {
+        0 => point.x,
+        _ => point.y.expect("a grid point has a y"),
+    }
+}
+
+fn set_coordinate(point: &mut Point, axis: usize, value: i64) {
+    match axis {
+        0 => point.x = value,
+        _ => point.y = Some(value),
+    }
+}
+
+// `42` on a line, `3,4` on a grid: the way they're typed in.
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.y {
+            Some(y) => write!(f, "{},{y}", self.x),
+            None => write!(f, "{}", self.x),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Temperature::*;
+
+    const FULL: RangeInclusive<i64> = i64::MIN..=i64::MAX;
+
+    fn line(x: i64) -> Point {
+        Point { x, y: None }
+    }
+
+    fn grid(x: i64, y: i64) -> Point {
+        Point { x, y: Some(y) }
+    }
+
+    fn answers(game: &mut HotCold, guesses: &[Point]) -> Vec<Temperature> {
+        guesses
+            .iter()
+            .map(|&guess| game.guess(guess).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn a_line_of_the_full_range() {
+        let mut game = HotCold::new(Board::Line, FULL, line(i64::MAX - 1));
+        let guesses = [
+            line(i64::MIN),
+            line(i64::MAX),
+            line(i64::MIN + 1),
+            line(i64::MAX - 3),
+            line(i64::MAX),
+            line(i64::MAX - 2),
+            line(i64::MAX - 1),
+        ];
+        assert_eq!(
+            answers(&mut game, &guesses),
+            [First, Warmer, Colder, Warmer, Warmer, Same, Found]
+        );
+    }
+
+    #[test]
+    fn a_grid_of_the_full_range() {
+        let secret = grid(i64::MAX, i64::MAX);
+        let mut game = HotCold::new(Board::Grid, FULL, secret);
+        // The first four are past u128::MAX squared, and only just apart; then under it again.
+        let guesses = [
+            grid(i64::MIN, i64::MIN),
+            grid(i64::MIN + 1, i64::MIN),
+            grid(i64::MIN, i64::MIN),
+            grid(i64::MIN, i64::MIN + 1),
+            grid(i64::MIN + 1, i64::MIN),
+            grid(i64::MIN, 0),
+            secret,
+        ];
+        assert_eq!(
+            answers(&mut game, &guesses),
+            [First, Warmer, Colder, Warmer, Same, Warmer, Found]
+        );
+
+        let corner = grid(i64::MIN, i64::MIN).distance(secret);
+        let expected = 2f64.sqrt() * 2f64.powi(64);
+        assert!((corner / expected - 1.0).abs() < 1e-12);
+    }
+
+    // Plays `game` with the solver until it's won, and returns the number of guesses.
+    fn solve(mut game: HotCold) -> u32 {
+        let mut solver = Solver::new(game.board(), game.range().clone());
+        while !game.is_finished() {
+            let guess = solver.next_guess();
+            let temperature = game.guess(guess).unwrap();
+            solver.update(guess, temperature);
+        }
+        assert_eq!(
+            game.outcome(),
+            Outcome::Won {
+                attempts: game.attempts()
+            }
+        );
+        game.attempts()
+    }
+
+    #[test]
+    fn the_solver_finds_every_number_on_a_line() {
+        let worst = (1..=100)
+            .map(|secret| solve(HotCold::new(Board::Line, 1..=100, line(secret))))
+            .max();
+        assert_eq!(worst, Some(13));
+        assert_eq!(solve(HotCold::new(Board::Line, 7..=7, line(7))), 1);
+    }
+
+    #[test]
+    fn the_solver_finds_every_point_on_a_grid() {
+        let mut worst = 0;
+        for x in 1..=20 {
+            for y in 1..=20 {
+                worst = worst.max(solve(HotCold::new(Board::Grid, 1..=20, grid(x, y))));
+            }
+        }
+        assert_eq!(worst, 17);
+    }
+
+    #[test]
+    fn the_solver_works_on_the_full_range() {
+        // At most two guesses per halving: 64 of them.
+        for secret in [i64::MIN, i64::MIN + 1, -1, 0, 1, i64::MAX - 1, i64::MAX] {
+            let attempts = solve(HotCold::new(Board::Line, FULL, line(secret)));
+            assert!(attempts <= 128, "{secret}: {attempts}");
+        }
+        let attempts = solve(HotCold::new(Board::Grid, FULL, grid(i64::MIN + 1, 0)));
+        assert!(attempts <= 2 * 128, "{attempts}");
+    }
+
+    #[test]
+    fn distances_are_exact() {
+        assert_eq!(grid(0, 0).distance(grid(3, 4)), 5.0);
+        assert_eq!(line(-2).distance(line(2)), 4.0);
+        assert_eq!(
+            grid(1, 7).distance_squared(grid(7, 1)),
+            grid(7, 1).distance_squared(grid(1, 7))
+        );
+        assert_eq!(line(9).distance_squared(line(9)), DistanceSquared::ZERO);
+    }
+}
@@ -0,0 +1,379 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::io;
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tiny_http::{Header, Response, Server};
+
+use crate::game::{Game, Outcome};
+use crate::seed;
+
+// ========== HTTP JSON API ==========
+/*
+`http` serves games over HTTP, so they can be driven from other tools (127.0.0.1:8080 by default).
+Every request and response body is JSON.
+
+    POST /games                  {"min": 1, "max": 100, "seed": 42, "max_attempts": 7}
+                                 All fields are optional; the range defaults to the one from the
+                                 command line. Answers 201 with the state of the new game.
+    POST /games/{id}/guesses     {"guess": 50}
+                                 Answers {"result": "less"|"greater"|"equal", "attempts": 1, ...}:
+                                 the guess compared to the secret, plus the state of the game.
+    GET  /games/{id}             The state of the game.
+
+The state of a game looks like:
+
+    {"id": 1, "min": 1, "max": 100, "seed": 42, "status": "in_progress", "attempts": 1,
+     "max_attempts": 7, "history": [{"guess": 50, "result": "less"}]}
+
+`status` is "in_progress", "won" or "lost"; once the game is over `secret` is added as well.
+Errors come back as {"error": "..."} with a 4xx status: 400 for a body that isn't valid,
+404 for an unknown (or expired) game, 409 for a guess on a finished game and 422 for a guess
+outside the range.
+
+A session that hasn't been used for the timeout (`--session-timeout`, 10 minutes by default)
+expires and is removed.
+
+`Api::handle` does all the work on plain values, the `serve` loop only moves them in and out of tiny_http.
+ */
+pub const DEFAULT_ADDR: &str = "127.0.0.1:8080";
+pub const DEFAULT_SESSION_TIMEOUT: Duration = Duration::from_secs(600);
+
+struct Session {
+    game: Game,
+    seed: u64,
+    last_used: Instant,
+}
+
+pub struct Api {
+    range: RangeInclusive<i64>,
+    timeout: Duration,
+    sessions: HashMap<u64, Session>,
+    next_id: u64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NewGame {
+    min: Option<i64>,
+    max: Option<i64>,
+    seed: Option<u64>,
+    max_attempts: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NewGuess {
+    guess: i64,
+}
+
+impl Api {
+    // `range` is used for games that don't ask for a range of their own.
+    pub fn new(range: RangeInclusive<i64>, timeout: Duration) -> Api {
+        Api {
+            range,
+            timeout,
+            sessions: HashMap::new(),
+            next_id: 1,
+        }
+    }
+
+    // Answers a request with a status code and a JSON body.
+    pub fn handle(&mut self, method: &str, path: &str, body: &str) -> (u16, Value) {
+        self.expire_sessions();
+
+        // The query string (if any) isn't used by any endpoint.
+        let path = path.split('?').next().unwrap_or("");
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        let result = match (method, segments.as_slice()) {
+            ("POST", ["games"]) => self.create(body),
+            ("GET", ["games", id]) => self.state(id),
+            ("POST", ["games", id, "guesses"]) => self.guess(id, body),
+            (_, ["games"]) | (_, ["games", _]) | (_, ["games", _, "guesses"]) => {
+                Err((405, format!("{method} is not allowed on {path}")))
+            }
+            _ => Err((404, format!("no such endpoint: {path}"))),
+        };
+
+        match result {
+            Ok(response) => response,
+            Err((status, message)) => (status, json!({ "error": message })),
+        }
+    }
+
+    fn create(&mut self, body: &str) -> Result<(u16, Value), (u16, String)> {
+        // An empty body is fine, it means "all defaults".
+        let request: NewGame = if body.trim().is_empty() {
+            NewGame::default()
+        } else {
+            serde_json::from_str(body).map_err(|err| (400, format!("invalid body: {err}")))?
+        };
+
+        let range =
+            request.min.unwrap_or(*self.range.start())..=request.max.unwrap_or(*self.range.end());
+        if range.is_empty() {
+            return Err((400, "min can't be larger than max".to_string()));
+        }
+        if request.max_attempts == Some(0) {
+            return Err((400, "max_attempts must be at least 1".to_string()));
+        }
+
+        let seed = request.seed.unwrap_or_else(seed::random_seed);
+        let game = Game::from_rng(range, &mut seed::rng_from_seed(seed))
+            .with_max_attempts(request.max_attempts);
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.sessions.insert(
+            id,
+            Session {
+                game,
+                seed,
+                last_used: Instant::now(),
+            },
+        );
+        Ok((201, self.describe(id)))
+    }
+
+    fn state(&mut self, id: &str) -> Result<(u16, Value), (u16, String)> {
+        let id = self.session_id(id)?;
+        Ok((200, self.describe(id)))
+    }
+
+    fn guess(&mut self, id: &str, body: &str) -> Result<(u16, Value), (u16, String)> {
+        let id = self.session_id(id)?;
+        let request: NewGuess =
+            serde_json::from_str(body).map_err(|err| (400, format!("invalid body: {err}")))?;
+
+        let session = self
+            .sessions
+            .get_mut(&id)
+            .expect("session_id checked it exists");
+        if session.game.is_finished() {
+            return Err((409, "the game is already finished".to_string()));
+        }
+        let ordering = session
+            .game
+            .guess(request.guess)
+            .map_err(|err| (422, err.to_string()))?;
+
+        let mut response = self.describe(id);
+        response["result"] = json!(result_name(ordering));
+        Ok((200, response))
+    }
+
+    // Looks the id up, and marks the session as used so it doesn't expire.
+    fn session_id(&mut self, id: &str) -> Result<u64, (u16, String)> {
+        let not_found = || (404, format!("no game with id {id}"));
+        let id: u64 = id.parse().map_err(|_| not_found())?;
+        let session = self.sessions.get_mut(&id).ok_or_else(not_found)?;
+        session.last_used = Instant::now();
+        Ok(id)
+    }
+
+    fn describe(&self, id: u64) -> Value {
+        let session = &self.sessions[&id];
+        let game = &session.game;
+        let history: Vec<Value> = game
+            .history()
+            .iter()
+            .map(|(guess, ordering)| json!({ "guess": guess, "result": result_name(*ordering) }))
+            .collect();
+        let status = match game.outcome() {
+            Outcome::InProgress => "in_progress",
+            Outcome::Won { .. } => "won",
+            Outcome::Lost { .. } => "lost",
+        };
+
+        let mut state = json!({
+            "id": id,
+            "min": game.range().start(),
+            "max": game.range().end(),
+            "seed": session.seed,
+            "status": status,
+            "attempts": game.attempts(),
+            "max_attempts": game.max_attempts(),
+            "history": history,
+        });
+        if game.is_finished() {
+            state["secret"] = json!(game.secret());
+        }
+        state
+    }
+
+    fn expire_sessions(&mut self) {
+        let timeout = self.timeout;
+        self.sessions
+            .retain(|_, session| session.last_used.elapsed() < timeout);
+    }
+}
+
+fn result_name(ordering: Ordering) -> &'static str {
+    match ordering {
+        Ordering::Less => "less",
+        Ordering::Greater => "greater",
+        Ordering::Equal => "equal",
+    }
+}
+
+// Handles requests one at a time, forever.
+pub fn serve(server: Server, mut api: Api) -> io::Result<()> {
+    for mut request in server.incoming_requests() {
+        let mut body = String::new();
+        let (status, value) = match request.as_reader().read_to_string(&mut body) {
+            Ok(_) => {
+                let method = request.method().to_string();
+                api.handle(&method, request.url(), &body)
+            }
+            Err(err) => (
+                400,
+                json!({ "error": format!("could not read the body: {err}") }),
+            ),
+        };
+
+        let content_type =
+            Header::from_bytes("Content-Type", "application/json").expect("the header is valid");
+        let response = Response::from_string(value.to_string())
+            .with_status_code(status)
+            .with_header(content_type);
+        request.respond(response)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    fn api() -> Api {
+        Api::new(1..=100, DEFAULT_SESSION_TIMEOUT)
+    }
+
+    // The secret a game created with `seed` gets.
+    fn secret(seed: u64) -> i64 {
+        Game::from_rng(1..=100, &mut seed::rng_from_seed(seed)).secret()
+    }
+
+    #[test]
+    fn create_answers_201_with_the_state() {
+        let mut api = api();
+        let (status, body) = api.handle("POST", "/games", r#"{"seed": 42, "max_attempts": 7}"#);
+        assert_eq!(status, 201);
+        assert_eq!(
+            body,
+            json!({
+                "id": 1, "min": 1, "max": 100, "seed": 42, "status": "in_progress",
+                "attempts": 0, "max_attempts": 7, "history": []
+            })
+        );
+        // An empty body takes every default.
+        let (status, body) = api.handle("POST", "/games", "");
+        assert_eq!(status, 201);
+        assert_eq!(body["id"], 2);
+        assert_eq!(body["max_attempts"], Value::Null);
+    }
+
+    #[test]
+    fn guesses_answer_200_until_the_game_is_won() {
+        let mut api = api();
+        api.handle("POST", "/games", r#"{"seed": 42}"#);
+        let secret = secret(42);
+
+        let guess = |api: &mut Api, guess: i64| {
+            api.handle(
+                "POST",
+                "/games/1/guesses",
+                &json!({ "guess": guess }).to_string(),
+            )
+        };
+        let (status, body) = guess(&mut api, secret - 1);
+        assert_eq!(status, 200);
+        assert_eq!(body["result"], "less");
+        assert_eq!(body["status"], "in_progress");
+        assert_eq!(body.get("secret"), None);
+
+        let (_, body) = guess(&mut api, secret);
+        assert_eq!(body["result"], "equal");
+        assert_eq!(body["status"], "won");
+        assert_eq!(body["attempts"], 2);
+        assert_eq!(body["secret"], secret);
+
+        let (status, body) = api.handle("GET", "/games/1?verbose", "");
+        assert_eq!(status, 200);
+        assert_eq!(
+            body["history"],
+            json!([
+                { "guess": secret - 1, "result": "less" },
+                { "guess": secret, "result": "equal" },
+            ])
+        );
+
+        let (status, _) = guess(&mut api, secret);
+        assert_eq!(status, 409);
+    }
+
+    #[test]
+    fn the_last_attempt_loses() {
+        let mut api = api();
+        api.handle("POST", "/games", r#"{"seed": 42, "max_attempts": 1}"#);
+        let wrong = if secret(42) == 1 { 2 } else { 1 };
+        let (_, body) = api.handle(
+            "POST",
+            "/games/1/guesses",
+            &format!(r#"{{"guess": {wrong}}}"#),
+        );
+        assert_eq!(body["status"], "lost");
+        assert_eq!(body["secret"], secret(42));
+    }
+
+    #[test]
+    fn bad_requests_answer_4xx() {
+        let mut api = api();
+        api.handle("POST", "/games", "");
+
+        let cases = [
+            ("POST", "/games", "{", 400),
+            ("POST", "/games", r#"{"colour": "red"}"#, 400),
+            ("POST", "/games", r#"{"min": 10, "max": 1}"#, 400),
+            ("POST", "/games", r#"{"max_attempts": 0}"#, 400),
+            ("POST", "/games/1/guesses", r#"{"guess": "fifty"}"#, 400),
+            ("GET", "/games/2", "", 404),
+            ("GET", "/games/one", "", 404),
+            ("POST", "/games/2/guesses", r#"{"guess": 50}"#, 404),
+            ("GET", "/players", "", 404),
+            ("DELETE", "/games/1", "", 405),
+            ("GET", "/games", "", 405),
+            ("GET", "/games/1/guesses", "", 405),
+            ("POST", "/games/1/guesses", r#"{"guess": 101}"#, 422),
+        ];
+        for (method, path, body, expected) in cases {
+            let (status, response) = api.handle(method, path, body);
+            assert_eq!(status, expected, "{method} {path} {body}");
+            assert!(response["error"].is_string(), "{method} {path} {body}");
+        }
+        // None of them counted as an attempt.
+        assert_eq!(api.handle("GET", "/games/1", "").1["attempts"], 0);
+    }
+
+    #[test]
+    fn unused_sessions_expire() {
+        let mut api = Api::new(1..=100, Duration::from_millis(50));
+        api.handle("POST", "/games", "");
+        api.handle("POST", "/games", "");
+
+        // Every request on a session keeps it alive.
+        for _ in 0..3 {
+            thread::sleep(Duration::from_millis(30));
+            assert_eq!(api.handle("GET", "/games/2", "").0, 200);
+        }
+        assert_eq!(api.handle("GET", "/games/1", "").0, 404);
+
+        thread::sleep(Duration::from_millis(60));
+        assert_eq!(api.handle("GET", "/games/2", "").0, 404);
+    }
+}
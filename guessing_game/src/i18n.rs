@@ -0,0 +1,279 @@
+#[cfg(test)]
+use std::cell::RefCell;
+use std::env;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use unic_langid::LanguageIdentifier;
+
+// ========== Localization ==========
+/*
+Every message the player sees comes from a catalog in the `locales` directory, written in Fluent
+(https://projectfluent.org): one `id = text` per message, with `{ $name }` for the values that go in,
+and plural forms picked by the count, e.g. `{ $attempts -> [one] ... *[other] ... }`.
+
+The catalogs are compiled into the binary. The language is `--lang <code>`, or else the one in the
+`LANG` environment variable (like `nl_NL.UTF-8`), or else English. English is also the fallback
+for a message a catalog doesn't have, so a missing translation shows up in English instead of
+breaking the game. The tests below make sure that never ships: every catalog has exactly the same
+messages, and every message the code asks for by name is in them.
+
+There is one language per process, set once at start by `init`. Code that runs before that
+(or without it, like the library on its own) gets English. Tests can switch a single thread to
+another language with `in_language`.
+ */
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+// The language code and its catalog. English comes first: it's the reference for all the others.
+pub const CATALOGS: [(&str, &str); 2] = [
+    ("en", include_str!("../locales/en.ftl")),
+    ("nl", include_str!("../locales/nl.ftl")),
+];
+
+struct Messages {
+    bundle: FluentBundle<FluentResource>,
+    // English, for messages missing from `bundle`. None when `bundle` is English already.
+    fallback: Option<FluentBundle<FluentResource>>,
+}
+
+static MESSAGES: OnceLock<Messages> = OnceLock::new();
+
+// Tests share the process, and with it the language. One that needs another language sets it
+// for its own thread with `in_language`.
+#[cfg(test)]
+thread_local! {
+    static THREAD_MESSAGES: RefCell<Option<Messages>> = const { RefCell::new(None) };
+}
+
+// Picks the language for the rest of the program. `lang` comes from `--lang` and has been checked
+// with `language`; without it `LANG` decides, and an unsupported `LANG` just means English.
+pub fn init(lang: Option<&str>) {
+    let language = match lang {
+        Some(lang) => language(lang),
+        None => env::var("LANG").ok().and_then(|lang| language(&lang)),
+    };
+    let language = language.unwrap_or(DEFAULT_LANGUAGE);
+    // A second call changes nothing: the language was already picked.
+    let _ = MESSAGES.set(Messages::new(language));
+}
+
+// The supported language for a code like `nl`, `nl-BE` or `nl_NL.UTF-8`, if there is one.
+pub fn language(code: &str) -> Option<&'static str> {
+    let code = code.split('.').next()?.replace('_', "-");
+    let id: LanguageIdentifier = code.parse().ok()?;
+    CATALOGS
+        .iter()
+        .map(|(language, _)| *language)
+        .find(|language| *language == id.language.as_str())
+}
+
+// The text of a message without any values in it.
+pub fn text(id: &str) -> String {
+    message(id, &[])
+}
+
+// The text of a message, with `args` filled in by name.
+pub fn message(id: &str, args: &[(&str, &dyn Display)]) -> String {
+    let args: FluentArgs = args
+        .iter()
+        .map(|(name, value)| (*name, value_of(value.to_string())))
+        .collect();
+
+    #[cfg(test)]
+    if let Some(text) = THREAD_MESSAGES
+        .with_borrow(|messages| messages.as_ref().map(|messages| messages.format(id, &args)))
+    {
+        return text;
+    }
+    MESSAGES
+        .get_or_init(|| Messages::new(DEFAULT_LANGUAGE))
+        .format(id, &args)
+}
+
+// Runs `f` with the messages of this thread in `language`, e.g. to check a translation.
+#[cfg(test)]
+pub fn in_language<T>(language: &str, f: impl FnOnce() -> T) -> T {
+    let before = THREAD_MESSAGES.replace(Some(Messages::new(language)));
+    let result = f();
+    THREAD_MESSAGES.set(before);
+    result
+}
+
+// Whole numbers go to Fluent as numbers, so they can pick a plural form. Fluent keeps numbers
+// as f64, so the ones too big for that (like seeds) stay text, or they'd be printed rounded.
+// So does anything that wouldn't be printed the same, like the alphabet `0123456789`.
+fn value_of(text: String) -> FluentValue<'static> {
+    const EXACT: i64 = 1 << f64::MANTISSA_DIGITS;
+    match text.parse::<i64>() {
+        Ok(number) if (-EXACT..=EXACT).contains(&number) && number.to_string() == text => {
+            FluentValue::from(number)
+        }
+        _ => FluentValue::from(text),
+    }
+}
+
+impl Messages {
+    fn new(language: &str) -> Messages {
+        Messages {
+            bundle: bundle(language),
+            fallback: (language != DEFAULT_LANGUAGE).then(|| bundle(DEFAULT_LANGUAGE)),
+        }
+    }
+
+    fn format(&self, id: &str, args: &FluentArgs) -> String {
+        [Some(&self.bundle), self.fallback.as_ref()]
+            .into_iter()
+            .flatten()
+            .find_map(|bundle| format(bundle, id, args))
+            // A message that's nowhere at all is a bug, but the id still says what was meant.
+            .unwrap_or_else(|| id.to_string())
+    }
+}
+
+fn bundle(language: &str) -> FluentBundle<FluentResource> {
+    let (_, source) = CATALOGS
+        .iter()
+        .find(|(code, _)| *code == language)
+        .expect("only supported languages get a bundle");
+    let id: LanguageIdentifier = language.parse().expect("language codes are valid");
+
+    let mut bundle = FluentBundle::new_concurrent(vec![id]);
+    // Fluent puts invisible Unicode marks around every value by default, for right-to-left
+    // languages. A terminal shows them as garbage, and neither English nor Dutch needs them.
+    bundle.set_use_isolating(false);
+    bundle
+        .add_resource(resource(source))
+        .expect("message ids are unique within a catalog");
+    bundle
+}
+
+// The catalogs are part of the binary, so a syntax error is a bug, not something to recover from.
+fn resource(source: &'static str) -> FluentResource {
+    FluentResource::try_new(source.to_string())
+        .unwrap_or_else(|(_, errors)| panic!("invalid catalog: {errors:?}"))
+}
+
+fn format(bundle: &FluentBundle<FluentResource>, id: &str, args: &FluentArgs) -> Option<String> {
+    let pattern = bundle.get_message(id)?.value()?;
+    let mut errors = Vec::new();
+    let text = bundle.format_pattern(pattern, Some(args), &mut errors);
+    Some(text.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::fs;
+    use std::path::Path;
+
+    use fluent_syntax::ast;
+
+    use super::*;
+
+    fn message_ids(source: &'static str) -> BTreeSet<String> {
+        resource(source)
+            .entries()
+            .filter_map(|entry| match entry {
+                ast::Entry::Message(message) => Some(message.id.name.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn every_catalog_has_every_message() {
+        let (_, english) = CATALOGS[0];
+        let reference = message_ids(english);
+        for (language, source) in &CATALOGS[1..] {
+            let translated = message_ids(source);
+            let missing: Vec<_> = reference.difference(&translated).collect();
+            let extra: Vec<_> = translated.difference(&reference).collect();
+            assert!(missing.is_empty(), "`{language}` is missing {missing:?}");
+            assert!(
+                extra.is_empty(),
+                "`{language}` has messages English doesn't: {extra:?}"
+            );
+        }
+    }
+
+    // Every `text("id")` and `message("id", ..)` in the source, and the ids that are put together
+    // at run time. Ids picked in a `match` before the call aren't seen.
+    #[test]
+    fn every_message_the_code_asks_for_exists() {
+        let (_, english) = CATALOGS[0];
+        let known = message_ids(english);
+        let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+
+        let (mut found, mut unknown) = (0, Vec::new());
+        for entry in fs::read_dir(src).unwrap() {
+            let path = entry.unwrap().path();
+            // This file has the calls below as text.
+            if path.ends_with("i18n.rs") {
+                continue;
+            }
+            let code = fs::read_to_string(&path).unwrap();
+            for call in ["i18n::text(", "i18n::message("] {
+                for (start, _) in code.match_indices(call) {
+                    let rest = code[start + call.len()..].trim_start();
+                    let Some(literal) = rest.strip_prefix('"') else {
+                        continue;
+                    };
+                    let id = &literal[..literal.find('"').unwrap()];
+                    found += 1;
+                    if !known.contains(id) {
+                        unknown.push(format!("{}: {id}", path.display()));
+                    }
+                }
+            }
+        }
+        for achievement in crate::achievements::achievements() {
+            let id = achievement.id();
+            for id in [
+                format!("achievement-{id}"),
+                format!("achievement-{id}-description"),
+            ] {
+                if !known.contains(&id) {
+                    unknown.push(format!("achievements.rs: {id}"));
+                }
+            }
+        }
+        assert!(unknown.is_empty(), "unknown messages: {unknown:?}");
+        // Make sure the scan itself works.
+        assert!(found > 100, "only {found} messages found");
+    }
+
+    #[test]
+    fn languages_are_picked_from_codes_and_locales() {
+        assert_eq!(language("nl"), Some("nl"));
+        assert_eq!(language("nl-BE"), Some("nl"));
+        assert_eq!(language("nl_NL.UTF-8"), Some("nl"));
+        assert_eq!(language("en_US"), Some("en"));
+        assert_eq!(language("fr_FR.UTF-8"), None);
+        assert_eq!(language(""), None);
+    }
+
+    #[test]
+    fn a_thread_can_switch_languages() {
+        assert_eq!(text("bye"), "Bye!");
+        assert_eq!(in_language("nl", || text("bye")), "Doei!");
+        assert_eq!(in_language("en", || text("bye")), "Bye!");
+        assert_eq!(text("bye"), "Bye!");
+    }
+
+    #[test]
+    fn numbers_pick_plural_forms_but_stay_exact() {
+        let bundle = bundle("en");
+        let format = |attempts: &str| {
+            let args: FluentArgs = [("attempts", value_of(attempts.to_string()))]
+                .into_iter()
+                .collect();
+            super::format(&bundle, "you-lose", &args).unwrap()
+        };
+        assert_eq!(format("1"), "You lose! No attempts left after 1 guess.");
+        assert_eq!(format("2"), "You lose! No attempts left after 2 guesses.");
+        assert!(format("18446744073709551615").contains("18446744073709551615"));
+        assert!(format("0123").contains("0123"));
+    }
+}
@@ -0,0 +1,144 @@
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead};
+use std::num::IntErrorKind;
+use std::ops::RangeInclusive;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use crate::i18n;
+
+// ========== Reading guesses ==========
+/*
+The original loop did `Err(_) => continue` on anything that didn't parse. That silently ignored typos,
+and at the end of the input `read_line` keeps returning Ok(0) with an empty string, so it spun forever.
+
+Every way a line can fail to be a valid guess now has its own variant, so the front-end can tell
+the player what went wrong, and stop when there's nothing left to read.
+ */
+#[derive(Debug)]
+pub enum InputError {
+    // `read_line` returned Ok(0): stdin was closed (Ctrl-D, or the end of a piped file).
+    Eof,
+    // The line was empty or contained something other than a whole number.
+    NotANumber(String),
+    // A negative number, while the range only holds positive numbers.
+    Negative(i64),
+    // The number doesn't fit in an i64.
+    Overflow(String),
+    // A valid number, but the secret can't be it.
+    OutOfRange { guess: i64, min: i64, max: i64 },
+    // Reading from stdin failed altogether.
+    Io(io::Error),
+    // Nothing came in before the deadline (see `LineReader`).
+    TimedOut,
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            InputError::Eof => i18n::text("input-eof"),
+            InputError::NotANumber(text) if text.is_empty() => i18n::text("input-empty"),
+            InputError::NotANumber(text) => i18n::message("input-not-a-number", &[("text", text)]),
+            InputError::Negative(guess) => i18n::message("input-negative", &[("guess", guess)]),
+            InputError::Overflow(text) => i18n::message("input-overflow", &[("text", text)]),
+            InputError::OutOfRange { guess, min, max } => i18n::message(
+                "out-of-range",
+                &[("guess", guess), ("min", min), ("max", max)],
+            ),
+            InputError::Io(err) => i18n::message("input-io", &[("error", err)]),
+            InputError::TimedOut => i18n::text("input-timed-out"),
+        };
+        f.write_str(&text)
+    }
+}
+
+impl Error for InputError {}
+
+impl From<io::Error> for InputError {
+    fn from(err: io::Error) -> InputError {
+        InputError::Io(err)
+    }
+}
+
+// Read one line, with the end of the input as an error instead of an empty string.
+pub fn read_line(reader: &mut impl BufRead) -> Result<String, InputError> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Err(InputError::Eof);
+    }
+    Ok(line)
+}
+
+// Read one line and turn it into a guess within `range`.
+pub fn read_guess(
+    reader: &mut impl BufRead,
+    range: &RangeInclusive<i64>,
+) -> Result<i64, InputError> {
+    parse_guess(&read_line(reader)?, range)
+}
+
+pub fn parse_guess(line: &str, range: &RangeInclusive<i64>) -> Result<i64, InputError> {
+    let text = line.trim();
+    let guess: i64 = text
+        .parse()
+        .map_err(|err: std::num::ParseIntError| match err.kind() {
+            IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+                InputError::Overflow(text.to_string())
+            }
+            _ => InputError::NotANumber(text.to_string()),
+        })?;
+
+    if guess < 0 && *range.start() >= 0 {
+        return Err(InputError::Negative(guess));
+    }
+    if !range.contains(&guess) {
+        return Err(InputError::OutOfRange {
+            guess,
+            min: *range.start(),
+            max: *range.end(),
+        });
+    }
+
+    Ok(guess)
+}
+
+// ========== Reading with a deadline ==========
+/*
+`read_line` blocks until the player presses Enter, and nothing can interrupt it: a countdown that
+should end the game while the player is still thinking would never get a word in.
+
+So for the timed modes a thread of its own does the reading, and sends every line over a channel.
+The game waits on the channel instead, with a timeout when the clock is running.
+The thread stops after the end of the input (or a read error), which it sends on as well.
+ */
+pub struct LineReader {
+    lines: Receiver<Result<String, InputError>>,
+}
+
+impl LineReader {
+    pub fn stdin() -> LineReader {
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || loop {
+            let line = read_line(&mut io::stdin().lock());
+            let last = line.is_err();
+            if sender.send(line).is_err() || last {
+                break;
+            }
+        });
+        LineReader { lines }
+    }
+
+    // Waits for the next line, for at most `timeout` (None waits as long as it takes).
+    pub fn read_line(&self, timeout: Option<Duration>) -> Result<String, InputError> {
+        let line = match timeout {
+            Some(timeout) => self.lines.recv_timeout(timeout).map_err(|err| match err {
+                RecvTimeoutError::Timeout => InputError::TimedOut,
+                RecvTimeoutError::Disconnected => InputError::Eof,
+            }),
+            None => self.lines.recv().map_err(|_| InputError::Eof),
+        };
+        line?
+    }
+}
@@ -0,0 +1,134 @@
+use std::io;
+use std::path::Path;
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::difficulty::Difficulty;
+use crate::game::Game;
+use crate::i18n;
+use crate::storage;
+
+// ========== Leaderboard ==========
+/*
+Every won game is recorded in `leaderboard.json` in the data dir (see storage.rs for the locking).
+The table is printed with `--leaderboard`, best score first, optionally filtered by name and difficulty.
+ */
+pub const FILE_NAME: &str = "leaderboard.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub name: String,
+    pub difficulty: Difficulty,
+    pub attempts: u32,
+    pub seconds: f64,
+    pub score: u32,
+    pub date: DateTime<Utc>,
+}
+
+impl Entry {
+    // Only won games make it to the leaderboard, so this is None for a game that was lost or is still running.
+    pub fn from_game(name: &str, difficulty: Difficulty, game: &Game) -> Option<Entry> {
+        Some(Entry {
+            name: name.to_string(),
+            difficulty,
+            attempts: game.attempts(),
+            seconds: game.elapsed().as_secs_f64(),
+            score: game.score()?,
+            date: Utc::now(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub entries: Vec<Entry>,
+}
+
+// What `--leaderboard` shows: `--name` and `--difficulty` narrow it down, `--top` limits the rows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    pub name: Option<String>,
+    pub difficulty: Option<Difficulty>,
+    pub top: usize,
+}
+
+impl Default for Filter {
+    fn default() -> Filter {
+        Filter {
+            name: None,
+            difficulty: None,
+            top: 10,
+        }
+    }
+}
+
+impl Leaderboard {
+    pub fn load(data_dir: &Path) -> io::Result<Leaderboard> {
+        storage::load(&data_dir.join(FILE_NAME))
+    }
+
+    pub fn record(data_dir: &Path, entry: Entry) -> io::Result<()> {
+        storage::update(
+            &data_dir.join(FILE_NAME),
+            |leaderboard: &mut Leaderboard| leaderboard.entries.push(entry),
+        )
+    }
+
+    // Best score first; on a tie the fewest attempts, then the earliest date wins.
+    pub fn top(&self, filter: &Filter) -> Vec<&Entry> {
+        let mut entries: Vec<&Entry> = self
+            .entries
+            .iter()
+            .filter(|entry| filter.name.as_ref().is_none_or(|name| &entry.name == name))
+            .filter(|entry| filter.difficulty.is_none_or(|d| entry.difficulty == d))
+            .collect();
+        entries.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then(a.attempts.cmp(&b.attempts))
+                .then(a.date.cmp(&b.date))
+        });
+        entries.truncate(filter.top);
+        entries
+    }
+}
+
+pub fn render(entries: &[&Entry]) -> String {
+    let [name, difficulty, attempts, time, score, date] = [
+        i18n::text("leaderboard-name"),
+        i18n::text("leaderboard-difficulty"),
+        i18n::text("leaderboard-attempts"),
+        i18n::text("leaderboard-time"),
+        i18n::text("leaderboard-score"),
+        i18n::text("leaderboard-date"),
+    ];
+    // Like the ratings table, a column grows to fit its header in the language it's in.
+    let column = |header: &str, values: usize| values.max(header.chars().count());
+    let name_width = column(&name, 16);
+    let difficulty_width = column(&difficulty, 10);
+    let attempts_width = column(&attempts, 8);
+    let time_width = column(&time, 8);
+    let score_width = column(&score, 6);
+
+    let mut table = format!(
+        "{:>4}  {name:<name_width$} {difficulty:<difficulty_width$} \
+         {attempts:>attempts_width$} {time:>time_width$} {score:>score_width$}  {date}\n",
+        "#"
+    );
+    for (rank, entry) in entries.iter().enumerate() {
+        table.push_str(&format!(
+            "{:>4}  {:<name_width$} {:<difficulty_width$} {:>attempts_width$} {:>seconds_width$.1}s \
+             {:>score_width$}  {}\n",
+            rank + 1,
+            entry.name,
+            entry.difficulty,
+            entry.attempts,
+            entry.seconds,
+            entry.score,
+            entry.date.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+            seconds_width = time_width - 1,
+        ));
+    }
+    table
+}
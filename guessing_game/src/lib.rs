@@ -0,0 +1,48 @@
+// ========== Guessing game engine ==========
+/*
+The game logic used to live inside the `loop` in main.rs, mixed in with `io::stdin()` and `println!`.
+That made it impossible to test, or to drive the game from anything other than a terminal.
+
+The library target holds the engine: a `Game` state machine that knows the secret, counts attempts
+and answers every guess with an `Ordering`. Front-ends (like the binary in main.rs) only do I/O.
+ */
+
+pub mod achievements;
+pub mod batch;
+pub mod bot;
+pub mod bulls_cows;
+pub mod config;
+pub mod difficulty;
+pub mod game;
+pub mod hints;
+pub mod histogram;
+pub mod host;
+pub mod hot_cold;
+pub mod http;
+pub mod i18n;
+pub mod input;
+pub mod leaderboard;
+pub mod multiplayer;
+pub mod plugin;
+pub mod ratings;
+pub mod reverse;
+pub mod save;
+pub mod score;
+pub mod secrets;
+pub mod seed;
+pub mod speedrun;
+pub mod stats;
+pub mod storage;
+pub mod tournament;
+pub mod transcript;
+pub mod tui;
+
+pub use bulls_cows::BullsAndCows;
+pub use config::{Config, ConfigError, Mode};
+pub use difficulty::Difficulty;
+pub use game::{Game, GuessError, Outcome};
+pub use host::Host;
+pub use hot_cold::HotCold;
+pub use input::InputError;
+pub use leaderboard::Leaderboard;
+pub use seed::GameRng;
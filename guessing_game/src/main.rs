@@ -1,45 +1,1112 @@
-use std::io;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::{env, io, process, thread};
 use std::cmp::Ordering;
-use rand::Rng;
+
+use guessing_game::achievements::{self, Achievements};
+use guessing_game::leaderboard::{self, Entry};
+use guessing_game::ratings::{self, Ratings};
+use guessing_game::multiplayer::{self, ClientMessage, ServerMessage};
+use guessing_game::plugin::Plugin;
+use guessing_game::input::LineReader;
+use guessing_game::secrets::SecretSource;
+use guessing_game::speedrun::{self, SpeedRun};
+use guessing_game::stats::{self, Stats};
+use guessing_game::transcript::{self, Event, Header, Recorder, Transcript};
+use guessing_game::hot_cold::{Board, HotCold};
+use guessing_game::{batch, bot, hints, histogram, http, i18n, reverse, tournament, tui};
+use guessing_game::{
+    input, save, seed, storage, BullsAndCows, Config, Difficulty, Game, GuessError, Host,
+    InputError, Leaderboard, Mode, Outcome,
+};
+
+// Exit codes, so scripts can tell how a game ended. 0 is a finished game (won or lost),
+// 1 a usage or I/O problem, 4 a reverse game given answers that contradict each other.
+// Ctrl-C follows the shell convention of 128 + SIGINT (2).
+const EXIT_EOF: i32 = 3;
+const EXIT_CONFLICT: i32 = 4;
+const EXIT_INTERRUPTED: i32 = 130;
 
 fn main() {
-    println!("Guess the number!");
+    let config = Config::build(env::args()).unwrap_or_else(|err| {
+        // `--lang` may not have been read, so `LANG` picks the language of the error.
+        i18n::init(None);
+        eprintln!("{}", i18n::message("config-failed", &[("error", &err)]));
+        process::exit(1);
+    });
+    // Messages are in the language of `--lang` or `LANG` from here on.
+    i18n::init(config.lang.as_deref());
+
+    // Ctrl-C would otherwise kill the process mid-line; say goodbye and exit with a code of its own.
+    ctrlc::set_handler(|| {
+        println!();
+        println!("{}", i18n::text("interrupted"));
+        process::exit(EXIT_INTERRUPTED);
+    })
+    .expect("Failed to set the Ctrl-C handler");
+
+    match config.mode {
+        Mode::Play => play(&config),
+        Mode::Leaderboard => show_leaderboard(&config),
+        Mode::Bot => run_bots(&config),
+        Mode::Reverse => play_reverse(&config),
+        Mode::BullsAndCows => play_bulls_and_cows(&config),
+        Mode::HotCold => play_hot_cold(&config),
+        Mode::Tui => play_tui(&config),
+        Mode::Serve => serve(&config),
+        Mode::Join => join(&config),
+        Mode::Http => serve_http(&config),
+        Mode::SpeedRun => speed_run(&config),
+        Mode::Batch => run_batch(&config),
+        Mode::Stats => show_stats(&config),
+        Mode::Replay => replay(&config),
+        Mode::Tournament => run_tournament(&config),
+        Mode::Achievements => show_achievements(&config),
+        Mode::Ratings => show_ratings(&config),
+    }
+}
+
+// No prompts and no messages on stdout: only the JSON transcript, so scripts can parse it.
+fn run_batch(config: &Config) {
+    let seed = config.seed.unwrap_or_else(seed::random_seed);
+    let mut rng = seed::rng_from_seed(seed);
+    let mut source = secret_source(config);
+    let game = Game::sourced(config.range.clone(), config.host, source.as_mut(), &mut rng)
+        .with_max_attempts(config.max_attempts);
+
+    let transcript = match &config.input {
+        Some(path) => {
+            File::open(path).and_then(|file| batch::run(game, seed, BufReader::new(file)))
+        }
+        None => batch::run(game, seed, io::stdin().lock()),
+    };
+    match transcript {
+        Ok(transcript) => println!(
+            "{}",
+            serde_json::to_string_pretty(&transcript).expect("a transcript is valid JSON")
+        ),
+        Err(err) => {
+            eprintln!("{}", i18n::message("batch-failed", &[("error", &err)]));
+            process::exit(1);
+        }
+    }
+}
+
+fn serve_http(config: &Config) {
+    let addr = config.addr.as_deref().unwrap_or(http::DEFAULT_ADDR);
+    let server = tiny_http::Server::http(addr).unwrap_or_else(|err| {
+        eprintln!("{}", i18n::message("listen-failed", &[("addr", &addr), ("error", &err)]));
+        process::exit(1);
+    });
+    println!("{}", i18n::message("http-serving", &[("addr", &addr)]));
+
+    let api = http::Api::new(config.range.clone(), config.session_timeout);
+    if let Err(err) = http::serve(server, api) {
+        eprintln!("{}", i18n::message("server-stopped", &[("error", &err)]));
+        process::exit(1);
+    }
+}
+
+fn serve(config: &Config) {
+    let addr = config.addr.as_deref().unwrap_or(multiplayer::DEFAULT_ADDR);
+    let seed = config.seed.unwrap_or_else(seed::random_seed);
+    let server = multiplayer::Server::bind(addr, config.range.clone(), seed)
+        .unwrap_or_else(|err| {
+            eprintln!("{}", i18n::message("listen-failed", &[("addr", &addr), ("error", &err)]));
+            process::exit(1);
+        });
+    println!(
+        "{}",
+        i18n::message(
+            "serve-hosting",
+            &[
+                ("min", config.range.start()),
+                ("max", config.range.end()),
+                ("addr", &addr),
+                ("seed", &seed),
+            ],
+        )
+    );
+
+    if let Err(err) = server.run() {
+        eprintln!("{}", i18n::message("server-stopped", &[("error", &err)]));
+        process::exit(1);
+    }
+}
+
+fn join(config: &Config) {
+    let addr = config.addr.as_deref().unwrap_or(multiplayer::DEFAULT_ADDR);
+    let mut client = multiplayer::Client::connect(addr).unwrap_or_else(|err| {
+        eprintln!("{}", i18n::message("connect-failed", &[("addr", &addr), ("error", &err)]));
+        process::exit(1);
+    });
+    let mut receiver = client.try_clone().expect("Failed to clone the connection");
+
+    // Messages from the server can arrive at any time (another player winning),
+    // so they are printed by a thread of their own while this one waits for input.
+    thread::spawn(move || loop {
+        match receiver.receive() {
+            Ok(Some(message)) => println!("{}", describe(&message)),
+            Ok(None) => {
+                println!("{}", i18n::text("server-closed"));
+                process::exit(0);
+            }
+            Err(err) => {
+                eprintln!("{}", i18n::message("connection-lost", &[("error", &err)]));
+                process::exit(1);
+            }
+        }
+    });
+
+    if let Some(name) = &config.name {
+        send_or_exit(&mut client, &ClientMessage::Name(name.clone()));
+    }
+    println!("{}", i18n::text("join-intro"));
+    loop {
+        let line = read_line_or_exit(&i18n::text("no-input"));
+        let message = match line.trim() {
+            "" => continue,
+            "quit" => ClientMessage::Quit,
+            guess => ClientMessage::Guess(guess.to_string()),
+        };
+        send_or_exit(&mut client, &message);
+        if message == ClientMessage::Quit {
+            break;
+        }
+    }
+}
+
+fn send_or_exit(client: &mut multiplayer::Client, message: &ClientMessage) {
+    if let Err(err) = client.send(message) {
+        eprintln!("{}", i18n::message("connection-lost", &[("error", &err)]));
+        process::exit(1);
+    }
+}
+
+fn describe(message: &ServerMessage) -> String {
+    match message {
+        ServerMessage::Welcome { round, min, max } => i18n::message(
+            "server-welcome",
+            &[("round", round), ("min", min), ("max", max)],
+        ),
+        ServerMessage::TooSmall(guess) => i18n::message("server-too-small", &[("guess", guess)]),
+        ServerMessage::TooBig(guess) => i18n::message("server-too-big", &[("guess", guess)]),
+        ServerMessage::Correct(guess) => i18n::message("server-correct", &[("guess", guess)]),
+        ServerMessage::Winner { name, attempts } => {
+            i18n::message("server-winner", &[("name", name), ("attempts", attempts)])
+        }
+        ServerMessage::Round { round, min, max } => i18n::message(
+            "server-round",
+            &[("round", round), ("min", min), ("max", max)],
+        ),
+        ServerMessage::Joined(name) => i18n::message("server-joined", &[("name", name)]),
+        ServerMessage::Left(name) => i18n::message("server-left", &[("name", name)]),
+        ServerMessage::Error(err) => i18n::message("server-error", &[("error", err)]),
+    }
+}
+
+fn play_tui(config: &Config) {
+    let seed = config.seed.unwrap_or_else(seed::random_seed);
+    let mut rng = seed::rng_from_seed(seed);
+    let mut source = secret_source(config);
+    let game = Game::sourced(config.range.clone(), config.host, source.as_mut(), &mut rng)
+        .with_max_attempts(config.max_attempts)
+        .with_time_limit(config.time_limit);
+    let subtitle = i18n::message(
+        "tui-subtitle",
+        &[
+            ("difficulty", &config.difficulty),
+            ("min", config.range.start()),
+            ("max", config.range.end()),
+            ("host", &config.host),
+            ("seed", &seed),
+        ],
+    );
+
+    let app = tui::run(tui::App::new(game, &subtitle)).unwrap_or_else(|err| {
+        eprintln!("{}", i18n::message("tui-failed", &[("error", &err)]));
+        process::exit(1);
+    });
+
+    // Back on the normal screen: leave a summary behind, like the line based game does.
+    let exit = app.exit();
+    let game = app.into_game();
+    match (exit, game.outcome()) {
+        (Some(tui::Exit::Interrupted), _) => {
+            println!("{}", i18n::text("interrupted"));
+            process::exit(EXIT_INTERRUPTED);
+        }
+        (_, Outcome::Won { attempts }) => {
+            let seconds = format!("{:.1}", game.elapsed().as_secs_f64());
+            let score = game.score().unwrap_or(0);
+            println!(
+                "{}",
+                i18n::message(
+                    "tui-summary-won",
+                    &[("attempts", &attempts), ("seconds", &seconds), ("score", &score)]
+                )
+            );
+        }
+        (_, Outcome::Lost { .. }) if game.timed_out() => {
+            println!("{}", i18n::text("time-up"));
+            println!("{}", i18n::message("secret-was", &[("secret", &game.secret())]));
+        }
+        (_, Outcome::Lost { attempts }) => println!(
+            "{}",
+            i18n::message(
+                "tui-summary-lost",
+                &[("attempts", &attempts), ("secret", &game.secret())]
+            )
+        ),
+        (_, Outcome::InProgress) => println!(
+            "{}",
+            i18n::message(
+                "tui-summary-quit",
+                &[("attempts", &game.attempts()), ("secret", &game.secret())]
+            )
+        ),
+    }
+    let round = ratings::round(&header(config, seed));
+    record_game(config, config.difficulty, Some(&round), &game);
+}
+
+// Read one line of input. Without input the game can't go on, so at the end of it print `farewell` and exit.
+fn read_line_or_exit(farewell: &str) -> String {
+    input::read_line(&mut io::stdin().lock()).unwrap_or_else(|err| exit_on(err, farewell))
+}
+
+// Same as `read_line_or_exit`, but the wait ends after `timeout`; that gives None.
+fn read_line_within(
+    lines: &LineReader,
+    timeout: Option<Duration>,
+    farewell: &str,
+) -> Option<String> {
+    match lines.read_line(timeout) {
+        Ok(line) => Some(line),
+        Err(InputError::TimedOut) => None,
+        Err(err) => exit_on(err, farewell),
+    }
+}
+
+fn exit_on(err: InputError, farewell: &str) -> ! {
+    if let InputError::Eof = err {
+        println!();
+        println!("{farewell}");
+        process::exit(EXIT_EOF);
+    }
+    eprintln!("{err}");
+    process::exit(1);
+}
+
+fn play_bulls_and_cows(config: &Config) {
+    let seed = config.seed.unwrap_or_else(seed::random_seed);
+    println!("{}", i18n::message("seed", &[("seed", &seed)]));
+    let mut rng = seed::rng_from_seed(seed);
 
-    // Generate random number with a generator local to the current thread and is seeded by the OS
-    // The range is specified as 1..=100 which means 1-100 inclusive
-    let secret_number = rand::thread_rng().gen_range(1..=100);  // By default an i32
-    // println!("The secret number is: {secret_number}");
+    let mut game = BullsAndCows::from_rng(config.length, &config.alphabet, &mut rng)
+        .unwrap_or_else(|err| {
+            eprintln!("{}", i18n::message("code-setup-failed", &[("error", &err)]));
+            process::exit(1);
+        })
+        .with_max_attempts(config.max_attempts);
+    println!(
+        "{}",
+        i18n::message(
+            "code-intro",
+            &[("length", &game.length()), ("alphabet", &game.alphabet())]
+        )
+    );
+    println!("{}", i18n::text("code-rules"));
 
     loop {
-        println!("Please input your guess...");
-
-
-        // Declare var to store input in
-        let mut guess = String::new();
-        // Get the input and place it in the var
-        io::stdin()
-            .read_line(&mut guess)// Need to make the reference mutable as well, hence `&mut guess`
-            .expect("Failed to read line!");    // Catch the Err variant of the Result Enum, or return the Ok value (n bytes of the user input)
-
-        // Cast guess to an unsigned 32 bit int. Parse() is the part converting from string to another type.
-        // Check if it's a num. If Ok, pass to var, if Err ask for input again
-        let guess: u32 = match guess.trim().parse() {
-            Ok(num) => num,
-            Err(_) => continue,     // _ is a catch all value; anything caught here will be checked
-        };   //.expect("Not a number, please type a number!");
-
-        println!("You guessed: {guess}");
-
-        // Ordering is a type with Enums `Less`, `Greater` & `Equal`. guess is compared to secret_number here
-        // Match checks which output cme from the compare, and executes on the match.
-        // Matches have arms that rep[resent a pattern to match against, and the code that should be run if matched
-        match guess.cmp(&secret_number) {
-            Ordering::Less => println!("Too small!"),
-            Ordering::Greater => println!("Too big!"),
-            Ordering::Equal => {
-                println!("You win!");
+        prompt(game.attempts_left(), None);
+
+        let line = read_line_or_exit(&i18n::message("code-no-input", &[("code", &game.secret())]));
+        match game.guess(&line) {
+            Ok(feedback) => println!("{feedback}"),
+            Err(err) => {
+                println!("{err}");
+                continue;
+            }
+        }
+
+        match game.outcome() {
+            Outcome::InProgress => {}
+            Outcome::Won { attempts } => {
+                println!("{}", i18n::message("code-won", &[("attempts", &attempts)]));
+                break;
+            }
+            Outcome::Lost { attempts } => {
+                println!("{}", i18n::message("you-lose", &[("attempts", &attempts)]));
+                println!("{}", i18n::message("code-was", &[("code", &game.secret())]));
+                break;
+            }
+        }
+    }
+}
+
+fn play_hot_cold(config: &Config) {
+    let seed = config.seed.unwrap_or_else(seed::random_seed);
+    println!("{}", i18n::message("seed", &[("seed", &seed)]));
+    let mut rng = seed::rng_from_seed(seed);
+    let mut source = secret_source(config);
+
+    let board = if config.grid { Board::Grid } else { Board::Line };
+    let mut game = HotCold::sourced(board, config.range.clone(), source.as_mut(), &mut rng)
+        .with_max_attempts(config.max_attempts);
+    let (min, max) = (game.range().start(), game.range().end());
+    let intro = match board {
+        Board::Line => "hot-intro",
+        Board::Grid => "hot-intro-grid",
+    };
+    println!("{}", i18n::message(intro, &[("min", min), ("max", max)]));
+    println!("{}", i18n::text("hot-rules"));
+
+    loop {
+        prompt(game.attempts_left(), None);
+
+        let farewell = i18n::message("hot-no-input", &[("secret", &game.secret())]);
+        let line = read_line_or_exit(&farewell);
+        let guess = match game.parse(&line) {
+            Ok(guess) => guess,
+            Err(err) => {
+                println!("{err}");
+                continue;
+            }
+        };
+        match game.guess(guess) {
+            Ok(temperature) => println!("{temperature}"),
+            Err(err) => {
+                println!("{err}");
+                continue;
+            }
+        }
+
+        match game.outcome() {
+            Outcome::InProgress => {}
+            Outcome::Won { attempts } => {
+                println!("{}", i18n::message("hot-won", &[("attempts", &attempts)]));
                 break;
             }
+            Outcome::Lost { attempts } => {
+                println!("{}", i18n::message("you-lose", &[("attempts", &attempts)]));
+                // How close the last guess got, to one decimal on a grid.
+                let (last, _) = game.history()[game.history().len() - 1];
+                let distance = format!("{:.1}", last.distance(game.secret()));
+                println!(
+                    "{}",
+                    i18n::message(
+                        "hot-secret-was",
+                        &[("secret", &game.secret()), ("distance", &distance)]
+                    )
+                );
+                break;
+            }
+        }
+    }
+}
+
+fn play_reverse(config: &Config) {
+    println!(
+        "{}",
+        i18n::message(
+            "reverse-intro",
+            &[("min", config.range.start()), ("max", config.range.end())]
+        )
+    );
+    println!("{}", i18n::text("reverse-rules"));
+
+    let mut guesser = reverse::Guesser::new(&config.range);
+    while guesser.found().is_none() {
+        let guess = guesser.next_guess();
+        println!("{}", i18n::message("reverse-ask", &[("guess", &guess)]));
+
+        let answer = read_line_or_exit(&i18n::text("no-input"));
+        let Some(ordering) = reverse::parse_answer(&answer) else {
+            println!("{}", i18n::text("reverse-bad-answer"));
+            continue;
+        };
+        if let Err(conflict) = guesser.answer(ordering) {
+            println!("{}", i18n::message("reverse-conflict", &[("conflict", &conflict)]));
+            process::exit(EXIT_CONFLICT);
+        }
+    }
+
+    if let Some(number) = guesser.found() {
+        println!(
+            "{}",
+            i18n::message(
+                "reverse-found",
+                &[("number", &number), ("guesses", &guesser.answers().len())]
+            )
+        );
+    }
+}
+
+fn run_bots(config: &Config) {
+    let strategies = match &config.strategy {
+        Some(name) => match bot::strategy(name) {
+            Some(strategy) => vec![strategy],
+            None => {
+                eprintln!("{}", i18n::message("bot-unknown-strategy", &[("name", name)]));
+                process::exit(1);
+            }
+        },
+        None => bot::strategies(),
+    };
+
+    let seed = config.seed.unwrap_or_else(seed::random_seed);
+    println!(
+        "{}",
+        i18n::message(
+            "bot-intro",
+            &[
+                ("games", &config.games),
+                ("host", &config.host),
+                ("min", config.range.start()),
+                ("max", config.range.end()),
+                ("seed", &seed),
+            ],
+        )
+    );
+
+    for mut strategy in strategies {
+        let report =
+            bot::benchmark(strategy.as_mut(), &config.range, config.host, config.games, seed)
+                .unwrap_or_else(|err| {
+                    eprintln!(
+                        "{}",
+                        i18n::message(
+                            "bot-invalid-guess",
+                            &[("name", &strategy.name()), ("error", &err)],
+                        )
+                    );
+                    process::exit(1);
+                });
+
+        println!();
+        println!(
+            "{}",
+            i18n::message(
+                "bot-report",
+                &[
+                    ("name", &report.strategy),
+                    ("mean", &format!("{:.2}", report.mean)),
+                    ("best", &report.best),
+                    ("worst", &report.worst),
+                ],
+            )
+        );
+        print!("{}", histogram::render(&report.distribution));
+    }
+}
+
+fn run_tournament(config: &Config) {
+    let mut bots: Vec<Plugin> = config
+        .bots
+        .iter()
+        .map(|path| {
+            Plugin::load(path, config.limits).unwrap_or_else(|err| {
+                eprintln!(
+                    "{}",
+                    i18n::message("bot-load-failed", &[("file", &path.display()), ("error", &err)])
+                );
+                process::exit(1);
+            })
+        })
+        .collect();
+
+    let seed = config.seed.unwrap_or_else(seed::random_seed);
+    let max_attempts = config.max_attempts.unwrap_or(tournament::DEFAULT_MAX_ATTEMPTS);
+    println!(
+        "{}",
+        i18n::message(
+            "tournament-intro",
+            &[
+                ("bots", &bots.len()),
+                ("games", &config.games),
+                ("host", &config.host),
+                ("min", config.range.start()),
+                ("max", config.range.end()),
+                ("seed", &seed),
+            ],
+        )
+    );
+    let (standings, scores) =
+        tournament::run(&mut bots, &config.range, config.host, config.games, max_attempts, seed);
+    println!();
+    print!("{}", tournament::render(&standings));
+
+    let event = format!(
+        "tournament, seed {seed}, {} to {}, {} host, {max_attempts} attempts",
+        config.range.start(),
+        config.range.end(),
+        config.host
+    );
+    let recorded = storage::data_dir(config.data_dir.as_deref())
+        .and_then(|dir| Ratings::record_tournament(&dir, &scores, &event));
+    match recorded {
+        Ok(changes) => {
+            println!();
+            for change in changes {
+                let (before, after) =
+                    (format!("{:.0}", change.before), format!("{:.0}", change.after));
+                let difference = format!("{:+.0}", change.after - change.before);
+                println!(
+                    "{}",
+                    i18n::message(
+                        "ratings-tournament",
+                        &[
+                            ("name", &change.name),
+                            ("before", &before),
+                            ("after", &after),
+                            ("difference", &difference),
+                        ]
+                    )
+                );
+            }
+        }
+        Err(err) => eprintln!("{}", i18n::message("ratings-failed", &[("error", &err)])),
+    }
+}
+
+fn show_ratings(config: &Config) {
+    let ratings = storage::data_dir(config.data_dir.as_deref())
+        .and_then(|dir| Ratings::load(&dir))
+        .unwrap_or_else(|err| {
+            eprintln!("{}", i18n::message("ratings-read-failed", &[("error", &err)]));
+            process::exit(1);
+        });
+
+    match &config.name {
+        None if ratings.players.is_empty() => println!("{}", i18n::text("ratings-empty")),
+        None => print!("{}", ratings::render(&ratings, &ratings.top(config.filter.top))),
+        Some(name) => match ratings.players.get(name) {
+            None => println!("{}", i18n::message("ratings-unknown", &[("name", name)])),
+            Some(rating) => {
+                let (value, deviation) =
+                    (format!("{:.0}", rating.rating), format!("{:.0}", rating.deviation));
+                println!(
+                    "{}",
+                    i18n::message(
+                        "ratings-history",
+                        &[("name", name), ("rating", &value), ("deviation", &deviation)]
+                    )
+                );
+                print!("{}", ratings::render_history(&ratings.history(name)));
+            }
+        },
+    }
+}
+
+fn show_leaderboard(config: &Config) {
+    let leaderboard = storage::data_dir(config.data_dir.as_deref())
+        .and_then(|dir| Leaderboard::load(&dir))
+        .unwrap_or_else(|err| {
+            eprintln!("{}", i18n::message("leaderboard-read-failed", &[("error", &err)]));
+            process::exit(1);
+        });
+
+    let entries = leaderboard.top(&config.filter);
+    if entries.is_empty() {
+        println!("{}", i18n::text("leaderboard-empty"));
+    } else {
+        print!("{}", leaderboard::render(&entries));
+    }
+}
+
+fn show_stats(config: &Config) {
+    let stats = storage::data_dir(config.data_dir.as_deref())
+        .and_then(|dir| Stats::load(&dir))
+        .unwrap_or_else(|err| {
+            eprintln!("{}", i18n::message("stats-read-failed", &[("error", &err)]));
+            process::exit(1);
+        });
+
+    let summary = stats.summary(&config.stats);
+    if summary.games == 0 {
+        println!("{}", i18n::message("stats-empty", &[("name", &config.stats.name)]));
+    } else {
+        println!("{}", i18n::message("stats-title", &[("name", &config.stats.name)]));
+        print!("{}", stats::render(&summary));
+    }
+}
+
+fn show_achievements(config: &Config) {
+    let name = config.player_name();
+    let progress = storage::data_dir(config.data_dir.as_deref())
+        .and_then(|dir| Achievements::load(&dir))
+        .map(|achievements| achievements.progress(&name))
+        .unwrap_or_else(|err| {
+            eprintln!("{}", i18n::message("achievements-read-failed", &[("error", &err)]));
+            process::exit(1);
+        });
+
+    let total = achievements::achievements().len();
+    println!(
+        "{}",
+        i18n::message(
+            "achievements-title",
+            &[("name", &name), ("unlocked", &progress.unlocked.len()), ("total", &total)]
+        )
+    );
+    print!("{}", achievements::render(&progress));
+}
+
+fn play(config: &Config) {
+    println!("{}", i18n::text("guess-the-number"));
+
+    let (mut game, difficulty, mut recorder, seed) = match &config.resume {
+        Some(path) => {
+            let (game, difficulty) = resume(config, path);
+            (game, difficulty, None, None)
+        }
+        None => {
+            // Every game is seeded, either with `--seed <u64>` or with a random seed.
+            // The seed is printed, so passing it back with `--seed` replays the same game.
+            let seed = config.seed.unwrap_or_else(seed::random_seed);
+            println!("{}", i18n::message("seed", &[("seed", &seed)]));
+            let mut rng = seed::rng_from_seed(seed);
+            let mut source = secret_source(config);
+
+            // The engine picks a secret within the configured range (1 to 100 inclusive by default).
+            // All the comparing and counting happens in the library; this loop only reads and prints.
+            let game = Game::sourced(config.range.clone(), config.host, source.as_mut(), &mut rng)
+                .with_max_attempts(config.max_attempts)
+                .with_time_limit(config.time_limit);
+            let recorder = config.record.as_deref().map(|path| start_recording(config, seed, path));
+            (game, config.difficulty, recorder, Some(seed))
+        }
+    };
+    println!(
+        "{}",
+        i18n::message(
+            "difficulty-range",
+            &[
+                ("difficulty", &difficulty),
+                ("min", game.range().start()),
+                ("max", game.range().end()),
+            ]
+        )
+    );
+    if game.host() == Host::Evil {
+        println!("{}", i18n::text("evil-host"));
+    }
+    println!("{}", i18n::text("commands"));
+    // println!("The secret number is: {}", game.secret());
+
+    // Lines are read on a thread of their own, so a countdown can end the game while we wait.
+    let lines = LineReader::stdin();
+    while !game.is_finished() {
+        prompt(game.attempts_left(), game.time_left());
+
+        // Read a line and parse it into a signed 64 bit int within the range.
+        // Every kind of bad input has its own InputError variant, with a message to show the player.
+        // Only the end of the input (or a broken stdin) stops the game; for anything else we ask again.
+        let farewell = i18n::message("no-input-secret", &[("secret", &game.secret())]);
+        let Some(line) = read_line_within(&lines, game.time_left(), &farewell) else {
+            game.check_time();
+            if game.timed_out() {
+                record_event(&mut recorder, &game, Event::Timeout);
+            }
+            continue;
+        };
+
+        // Lines starting with `:` are commands, not guesses.
+        // :hint takes the name of a hint, :save and :quit an optional file to save to.
+        if let Some(command) = line.trim().strip_prefix(':') {
+            let (command, argument) = match command.split_once(' ') {
+                Some((command, argument)) => (command, Some(argument.trim())),
+                None => (command, None),
+            };
+            let path = argument.map(PathBuf::from).or_else(|| config.resume.clone());
+            match command {
+                "hint" => give_hint(&mut game, argument),
+                "save" => {
+                    save_game(config, &game, difficulty, path);
+                }
+                "quit" => {
+                    if save_game(config, &game, difficulty, path) {
+                        println!("{}", i18n::text("bye"));
+                        return;
+                    }
+                }
+                _ => println!("{}", i18n::message("unknown-command", &[("command", &command)])),
+            }
+            continue;
+        }
+
+        record_event(&mut recorder, &game, Event::Input(line.clone()));
+        let response = submit_guess(&mut game, &line);
+        record_event(&mut recorder, &game, Event::Response(response.to_string()));
+    }
+    let outcome = transcript::outcome_code(game.outcome());
+    record_event(&mut recorder, &game, Event::Outcome(outcome.to_string()));
+
+    // A wrong guess can also end the game, when it used up the last attempt, and so can the clock.
+    match game.outcome() {
+        Outcome::InProgress => {}
+        Outcome::Won { attempts } => {
+            let seconds = format!("{:.1}", game.elapsed().as_secs_f64());
+            let score = game.score().unwrap_or(0);
+            println!(
+                "{}",
+                i18n::message(
+                    "found-it",
+                    &[("attempts", &attempts), ("seconds", &seconds), ("score", &score)]
+                )
+            );
+        }
+        Outcome::Lost { attempts } => {
+            if game.timed_out() {
+                println!("{}", i18n::text("time-up"));
+            } else {
+                println!("{}", i18n::message("you-lose", &[("attempts", &attempts)]));
+            }
+            println!("{}", i18n::message("secret-was", &[("secret", &game.secret())]));
+        }
+    }
+    let round = seed.map(|seed| ratings::round(&header(config, seed)));
+    record_game(config, difficulty, round.as_deref(), &game);
+}
+
+// Parses a line into a guess and hands it to the engine, printing whatever happens.
+// Returns the response the way a transcript has it.
+fn submit_guess(game: &mut Game, line: &str) -> &'static str {
+    let guess = match input::parse_guess(line, game.range()) {
+        Ok(num) => num,
+        Err(err) => {
+            println!("{err}");
+            return batch::error_code(&err);
+        }
+    };
+
+    println!("{}", i18n::message("you-guessed", &[("guess", &guess)]));
+
+    // The engine answers with an Ordering (`Less`, `Greater` or `Equal`), we only decide what to print.
+    // Guesses outside the range are rejected by the engine; tell the player why and ask again.
+    // A guess after the time limit ends the game, which is reported with the outcome.
+    match game.guess(guess) {
+        Ok(ordering) => {
+            println!("{}", answer(ordering));
+            transcript::answer_code(ordering)
+        }
+        Err(err) => {
+            if !matches!(err, GuessError::TimeUp) {
+                println!("{err}");
+            }
+            batch::guess_error_code(&err)
+        }
+    }
+}
+
+// The setup of a game, as a transcript starts with it and as the ratings tell rounds apart.
+fn header(config: &Config, seed: u64) -> Header {
+    Header {
+        seed,
+        range: config.range.clone(),
+        host: config.host,
+        secrets: config.secrets.clone(),
+        max_attempts: config.max_attempts,
+        time_limit: config.time_limit,
+    }
+}
+
+fn start_recording(config: &Config, seed: u64, path: &Path) -> Recorder {
+    Recorder::create(path, &header(config, seed)).unwrap_or_else(|err| {
+        eprintln!("{}", i18n::message("transcript-record-failed", &[("error", &err)]));
+        process::exit(1);
+    })
+}
+
+// A transcript that can't be written shouldn't spoil the game, so recording stops with a warning.
+fn record_event(recorder: &mut Option<Recorder>, game: &Game, event: Event) {
+    if let Some(file) = recorder {
+        if let Err(err) = file.record(game.elapsed(), &event) {
+            eprintln!("{}", i18n::message("transcript-record-failed", &[("error", &err)]));
+            *recorder = None;
+        }
+    }
+}
+
+// Fails (with exit code 1) on the first answer that differs, so it can run as a regression test.
+fn replay(config: &Config) {
+    let path = config.input.as_deref().expect("`replay` always comes with a file");
+    let result = Transcript::load(path).and_then(|transcript| transcript::replay(&transcript));
+    match result {
+        Ok(inputs) => println!(
+            "{}",
+            i18n::message("replayed", &[("file", &path.display()), ("inputs", &inputs)])
+        ),
+        Err(err) => {
+            eprintln!(
+                "{}",
+                i18n::message("replay-failed", &[("file", &path.display()), ("error", &err)])
+            );
+            process::exit(1);
+        }
+    }
+}
+
+fn speed_run(config: &Config) {
+    let seed = config.seed.unwrap_or_else(seed::random_seed);
+    println!("{}", i18n::message("seed", &[("seed", &seed)]));
+    let mut rng = seed::rng_from_seed(seed);
+    let mut source = secret_source(config);
+
+    println!(
+        "{}",
+        i18n::message(
+            "speedrun-intro",
+            &[
+                ("rounds", &config.rounds),
+                ("min", config.range.start()),
+                ("max", config.range.end()),
+            ]
+        )
+    );
+
+    let lines = LineReader::stdin();
+    let mut run = SpeedRun::new(config.rounds);
+    while !run.is_finished() {
+        println!();
+        println!(
+            "{}",
+            i18n::message(
+                "speedrun-round",
+                &[("round", &run.round()), ("rounds", &run.rounds())]
+            )
+        );
+
+        // Every round gets a new secret from the same seeded generator, so a run can be replayed.
+        let mut game = Game::sourced(config.range.clone(), config.host, source.as_mut(), &mut rng)
+            .with_max_attempts(config.max_attempts)
+            .with_time_limit(config.time_limit);
+        while !game.is_finished() {
+            prompt(game.attempts_left(), game.time_left());
+            let farewell = i18n::message("no-input-secret", &[("secret", &game.secret())]);
+            match read_line_within(&lines, game.time_left(), &farewell) {
+                Some(line) => {
+                    submit_guess(&mut game, &line);
+                }
+                None => game.check_time(),
+            }
+        }
+
+        let split = run.record(&game);
+        let time = format!("{:.1}", split.time.as_secs_f64());
+        let total = format!("{:.1}", split.total.as_secs_f64());
+        if split.won {
+            println!(
+                "{}",
+                i18n::message("speedrun-split", &[("time", &time), ("total", &total)])
+            );
+        } else {
+            if game.timed_out() {
+                println!("{}", i18n::text("time-up"));
+            } else {
+                println!("{}", i18n::message("you-lose", &[("attempts", &split.attempts)]));
+            }
+            println!("{}", i18n::message("secret-was", &[("secret", &game.secret())]));
+        }
+        let round = ratings::speed_run_round(&header(config, seed), split.round, run.rounds());
+        record_game(config, config.difficulty, Some(&round), &game);
+    }
+
+    let won = run.splits().iter().filter(|split| split.won).count();
+    let total = format!("{:.1}", run.total().as_secs_f64());
+    println!();
+    print!("{}", speedrun::render(run.splits()));
+    println!(
+        "{}",
+        i18n::message(
+            "speedrun-total",
+            &[("total", &total), ("won", &won), ("rounds", &run.rounds())]
+        )
+    );
+}
+
+// Every finished game goes into the statistics, and a win on the leaderboard as well.
+// Files that can't be written shouldn't spoil the game, so problems are only reported.
+// `round` is what the game is rated in (see ratings::round), None for a resumed game, which isn't
+// rated.
+fn record_game(config: &Config, difficulty: Difficulty, round: Option<&str>, game: &Game) {
+    let name = config.player_name();
+    let dir = match storage::data_dir(config.data_dir.as_deref()) {
+        Ok(dir) => dir,
+        Err(err) => {
+            eprintln!("{}", i18n::message("leaderboard-failed", &[("error", &err)]));
+            return;
+        }
+    };
+
+    if let Some(record) = stats::Record::from_game(&name, difficulty, game) {
+        if let Err(err) = Stats::record(&dir, record) {
+            eprintln!("{}", i18n::message("stats-failed", &[("error", &err)]));
+        }
+    }
+    if let Some(entry) = Entry::from_game(&name, difficulty, game) {
+        if let Err(err) = Leaderboard::record(&dir, entry) {
+            eprintln!("{}", i18n::message("leaderboard-failed", &[("error", &err)]));
+        }
+    }
+
+    let events = achievements::events(game, difficulty);
+    match Achievements::record(&dir, &name, &events) {
+        Ok(unlocked) => {
+            for id in unlocked {
+                let name = achievements::name(id);
+                let description = achievements::description(id);
+                println!(
+                    "{}",
+                    i18n::message(
+                        "achievement-unlocked",
+                        &[("name", &name), ("description", &description)]
+                    )
+                );
+            }
         }
+        Err(err) => eprintln!("{}", i18n::message("achievements-failed", &[("error", &err)])),
     }
-}
\ No newline at end of file
+
+    let (Some(round), Some(entry)) = (round, ratings::Entry::from_game(&name, game)) else {
+        return;
+    };
+    match Ratings::record(&dir, round, entry) {
+        Ok(Some(change)) => {
+            let rating = format!("{:.0}", change.after);
+            let difference = format!("{:+.0}", change.after - change.before);
+            println!(
+                "{}",
+                i18n::message(
+                    "rating-changed",
+                    &[("rating", &rating), ("difference", &difference), ("players", &change.games)]
+                )
+            );
+        }
+        Ok(None) => {}
+        Err(err) => eprintln!("{}", i18n::message("ratings-failed", &[("error", &err)])),
+    }
+}
+
+fn resume(config: &Config, path: &Path) -> (Game, Difficulty) {
+    let result = storage::data_dir(config.data_dir.as_deref())
+        .and_then(|dir| save::key(&dir))
+        .map_err(save::SaveError::from)
+        .and_then(|key| save::load(path, &key));
+    match result {
+        Ok((game, difficulty)) => {
+            let seconds = format!("{:.1}", game.elapsed().as_secs_f64());
+            println!(
+                "{}",
+                i18n::message(
+                    "resumed",
+                    &[
+                        ("file", &path.display()),
+                        ("attempts", &game.attempts()),
+                        ("seconds", &seconds),
+                    ]
+                )
+            );
+            for (guess, ordering) in game.history() {
+                println!("  {guess}: {}", answer(*ordering));
+            }
+            (game, difficulty)
+        }
+        Err(err) => {
+            eprintln!(
+                "{}",
+                i18n::message("resume-failed", &[("file", &path.display()), ("error", &err)])
+            );
+            process::exit(1);
+        }
+    }
+}
+
+// Saves to `path`, or to the default file in the data dir. Returns whether it worked.
+fn save_game(config: &Config, game: &Game, difficulty: Difficulty, path: Option<PathBuf>) -> bool {
+    let result = storage::data_dir(config.data_dir.as_deref()).and_then(|dir| {
+        let key = save::key(&dir)?;
+        Ok((path.unwrap_or_else(|| save::default_path(&dir)), key))
+    });
+    let (path, key) = match result {
+        Ok(found) => found,
+        Err(err) => {
+            println!("{}", i18n::message("save-failed", &[("error", &err)]));
+            return false;
+        }
+    };
+
+    match save::save(&path, game, difficulty, &key) {
+        Ok(()) => {
+            println!("{}", i18n::message("saved", &[("file", &path.display())]));
+            true
+        }
+        Err(err) => {
+            println!("{}", i18n::message("save-failed", &[("error", &err)]));
+            false
+        }
+    }
+}
+
+fn give_hint(game: &mut Game, name: Option<&str>) {
+    let Some(name) = name else {
+        println!("{}", i18n::text("hints-list"));
+        for hint in hints::hints() {
+            println!("  :hint {:<10} -{}%", hint.name(), hint.cost());
+        }
+        return;
+    };
+
+    match hints::hint(name) {
+        Some(hint) => match game.hint(hint.as_ref()) {
+            Ok(clue) => println!(
+                "{}",
+                i18n::message("hint-given", &[("clue", &clue), ("cost", &hint.cost())])
+            ),
+            Err(err) => println!("{}", i18n::message("no-hint", &[("error", &err)])),
+        },
+        None => {
+            let err = hints::HintError::Unknown(name.to_string());
+            println!("{}", i18n::message("no-hint", &[("error", &err)]))
+        }
+    }
+}
+
+// The time left is rounded up to whole seconds: "0s left" would be over already.
+fn prompt(attempts_left: Option<u32>, time_left: Option<Duration>) {
+    let seconds = time_left.map(|left| left.as_millis().div_ceil(1000));
+    let text = match (attempts_left, seconds) {
+        (Some(left), Some(seconds)) => i18n::message(
+            "prompt-attempts-and-time-left",
+            &[("left", &left), ("seconds", &seconds)],
+        ),
+        (Some(left), None) => i18n::message("prompt-attempts-left", &[("left", &left)]),
+        (None, Some(seconds)) => i18n::message("prompt-time-left", &[("seconds", &seconds)]),
+        (None, None) => i18n::text("prompt"),
+    };
+    println!("{text}");
+}
+
+// What the host says to a guess.
+fn answer(ordering: Ordering) -> String {
+    i18n::text(match ordering {
+        Ordering::Less => "too-small",
+        Ordering::Greater => "too-big",
+        Ordering::Equal => "you-win",
+    })
+}
+
+// Reads the file of `--secrets file:<path>`, and makes sure its numbers fit the range.
+fn secret_source(config: &Config) -> Box<dyn SecretSource> {
+    let source = config.secrets.source().and_then(|source| {
+        source.check(&config.range)?;
+        Ok(source)
+    });
+    source.unwrap_or_else(|err| {
+        eprintln!("{}", i18n::message("secrets-failed", &[("error", &err)]));
+        process::exit(1);
+    })
+}
@@ -0,0 +1,404 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::game::Game;
+use crate::input;
+use crate::seed::{self, GameRng};
+
+// ========== Multiplayer over TCP ==========
+/*
+`serve` hosts rounds on a TCP port (127.0.0.1:7878 by default), `join` connects to it.
+All players race to find the same secret. Each of them only hears about their own guesses;
+the first one to find it is announced to everyone, and a new round starts right away.
+
+The server works like the web server in chapter 20 of the book: a thread per connection,
+with the shared state behind an `Arc<Mutex<..>>`. Messages are written while holding the lock, so
+a player that stops reading could hold up everybody: a write that takes longer than
+`WRITE_TIMEOUT` disconnects that player instead.
+
+---------- Protocol ----------
+Plain text, one message per line (`\n` terminated), fields separated by single spaces.
+
+Client to server:
+    NAME <name>           Pick a name (no spaces). Optional, the default is `player<id>`.
+                          Can be sent again later, to change it.
+    GUESS <number>        Guess the secret of the current round.
+    QUIT                  Leave; closing the connection works as well.
+
+Server to client:
+    WELCOME <round> <min> <max>    Sent once after connecting: the round that's on and its range.
+    TOO_SMALL <number>             Your guess was smaller than the secret.
+    TOO_BIG <number>               Your guess was bigger than the secret.
+    CORRECT <number>               You found it (a WINNER message for everyone follows).
+    WINNER <name> <attempts>       Broadcast: somebody found the secret of this round.
+    ROUND <round> <min> <max>      Broadcast: a new round started, with a new secret.
+    JOINED <name>                  Broadcast: somebody joined, right after connecting with the
+                                   default name, and again for every NAME they send.
+    LEFT <name>                    Broadcast: somebody left.
+    ERROR <message>                Your last message wasn't valid; the message may contain spaces.
+
+Example session (> from the client, < from the server):
+    < WELCOME 1 1 100
+    < JOINED player1
+    > NAME ada
+    < JOINED ada
+    > GUESS 50
+    < TOO_SMALL 50
+    > GUESS 75
+    < CORRECT 75
+    < WINNER ada 2
+    < ROUND 2 1 100
+ */
+pub const DEFAULT_ADDR: &str = "127.0.0.1:7878";
+const WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientMessage {
+    Name(String),
+    Guess(String),
+    Quit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerMessage {
+    Welcome { round: u32, min: i64, max: i64 },
+    TooSmall(i64),
+    TooBig(i64),
+    Correct(i64),
+    Winner { name: String, attempts: u32 },
+    Round { round: u32, min: i64, max: i64 },
+    Joined(String),
+    Left(String),
+    Error(String),
+}
+
+// The wire format: Display writes a message exactly as it goes over the connection (without the newline).
+impl fmt::Display for ClientMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClientMessage::Name(name) => write!(f, "NAME {name}"),
+            ClientMessage::Guess(guess) => write!(f, "GUESS {guess}"),
+            ClientMessage::Quit => write!(f, "QUIT"),
+        }
+    }
+}
+
+impl FromStr for ClientMessage {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<ClientMessage, String> {
+        let (command, argument) = split_command(line);
+        match (command, argument) {
+            ("NAME", Some(name)) if !name.contains(' ') => {
+                Ok(ClientMessage::Name(name.to_string()))
+            }
+            ("NAME", _) => Err("NAME needs a name without spaces".to_string()),
+            ("GUESS", Some(guess)) => Ok(ClientMessage::Guess(guess.to_string())),
+            ("GUESS", None) => Err("GUESS needs a number".to_string()),
+            ("QUIT", None) => Ok(ClientMessage::Quit),
+            _ => Err(format!("unknown message `{}`", line.trim())),
+        }
+    }
+}
+
+impl fmt::Display for ServerMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ServerMessage::Welcome { round, min, max } => write!(f, "WELCOME {round} {min} {max}"),
+            ServerMessage::TooSmall(guess) => write!(f, "TOO_SMALL {guess}"),
+            ServerMessage::TooBig(guess) => write!(f, "TOO_BIG {guess}"),
+            ServerMessage::Correct(guess) => write!(f, "CORRECT {guess}"),
+            ServerMessage::Winner { name, attempts } => write!(f, "WINNER {name} {attempts}"),
+            ServerMessage::Round { round, min, max } => write!(f, "ROUND {round} {min} {max}"),
+            ServerMessage::Joined(name) => write!(f, "JOINED {name}"),
+            ServerMessage::Left(name) => write!(f, "LEFT {name}"),
+            ServerMessage::Error(message) => write!(f, "ERROR {message}"),
+        }
+    }
+}
+
+impl FromStr for ServerMessage {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<ServerMessage, String> {
+        let invalid = || format!("invalid message `{}`", line.trim());
+        let (command, argument) = split_command(line);
+        let fields: Vec<&str> = argument.unwrap_or("").split(' ').collect();
+        let number = |i: usize| -> Result<i64, String> {
+            fields
+                .get(i)
+                .and_then(|field| field.parse().ok())
+                .ok_or_else(invalid)
+        };
+
+        match command {
+            "WELCOME" | "ROUND" => {
+                let round = number(0)? as u32;
+                let (min, max) = (number(1)?, number(2)?);
+                Ok(match command {
+                    "WELCOME" => ServerMessage::Welcome { round, min, max },
+                    _ => ServerMessage::Round { round, min, max },
+                })
+            }
+            "TOO_SMALL" => Ok(ServerMessage::TooSmall(number(0)?)),
+            "TOO_BIG" => Ok(ServerMessage::TooBig(number(0)?)),
+            "CORRECT" => Ok(ServerMessage::Correct(number(0)?)),
+            "WINNER" => Ok(ServerMessage::Winner {
+                name: fields[0].to_string(),
+                attempts: number(1)? as u32,
+            }),
+            "JOINED" => Ok(ServerMessage::Joined(
+                argument.ok_or_else(invalid)?.to_string(),
+            )),
+            "LEFT" => Ok(ServerMessage::Left(
+                argument.ok_or_else(invalid)?.to_string(),
+            )),
+            "ERROR" => Ok(ServerMessage::Error(argument.unwrap_or("").to_string())),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+// "GUESS 50" -> ("GUESS", Some("50")), "QUIT" -> ("QUIT", None).
+fn split_command(line: &str) -> (&str, Option<&str>) {
+    match line.trim().split_once(' ') {
+        Some((command, argument)) => (command, Some(argument.trim())),
+        None => (line.trim(), None),
+    }
+}
+
+// ========== Server ==========
+struct Player {
+    name: String,
+    stream: TcpStream,
+    // Every player has a game of their own (with the same secret), so attempts are counted per player.
+    game: Game,
+}
+
+struct State {
+    range: RangeInclusive<i64>,
+    rng: GameRng,
+    round: u32,
+    secret: i64,
+    players: HashMap<u64, Player>,
+    next_id: u64,
+}
+
+impl State {
+    fn new_round(&mut self) {
+        self.round += 1;
+        self.secret = self.rng.gen_range(self.range.clone());
+        for player in self.players.values_mut() {
+            player.game = Game::new(self.range.clone(), self.secret);
+        }
+    }
+
+    fn send(&mut self, id: u64, message: &ServerMessage) {
+        if let Some(player) = self.players.get_mut(&id) {
+            player.send(message);
+        }
+    }
+
+    fn broadcast(&mut self, message: &ServerMessage) {
+        for player in self.players.values_mut() {
+            player.send(message);
+        }
+    }
+}
+
+impl Player {
+    // A player that can't be written to (in time) is disconnected. That ends the read loop on
+    // their own thread, which removes them.
+    fn send(&mut self, message: &ServerMessage) {
+        if writeln!(self.stream, "{message}").is_err() {
+            let _ = self.stream.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+pub struct Server {
+    listener: TcpListener,
+    state: Arc<Mutex<State>>,
+}
+
+impl Server {
+    // The secrets of all rounds come from `seed`, so a whole session can be replayed.
+    pub fn bind(
+        addr: impl ToSocketAddrs,
+        range: RangeInclusive<i64>,
+        seed: u64,
+    ) -> io::Result<Server> {
+        let listener = TcpListener::bind(addr)?;
+        let mut state = State {
+            range,
+            rng: seed::rng_from_seed(seed),
+            round: 0,
+            secret: 0,
+            players: HashMap::new(),
+            next_id: 1,
+        };
+        state.new_round();
+
+        Ok(Server {
+            listener,
+            state: Arc::new(Mutex::new(state)),
+        })
+    }
+
+    // Useful after binding to port 0, to find out which port was picked.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    // Accepts players forever, every connection on a thread of its own.
+    pub fn run(self) -> io::Result<()> {
+        for stream in self.listener.incoming() {
+            let stream = stream?;
+            let state = Arc::clone(&self.state);
+            thread::spawn(move || {
+                if let Err(err) = handle_player(stream, state) {
+                    eprintln!("Connection error: {err}");
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+fn handle_player(stream: TcpStream, state: Arc<Mutex<State>>) -> io::Result<()> {
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    let reader = BufReader::new(stream.try_clone()?);
+
+    let id = {
+        let mut state = state.lock().unwrap();
+        let id = state.next_id;
+        state.next_id += 1;
+        let name = format!("player{id}");
+        let player = Player {
+            name: name.clone(),
+            stream,
+            game: Game::new(state.range.clone(), state.secret),
+        };
+        state.players.insert(id, player);
+        let welcome = ServerMessage::Welcome {
+            round: state.round,
+            min: *state.range.start(),
+            max: *state.range.end(),
+        };
+        state.send(id, &welcome);
+        state.broadcast(&ServerMessage::Joined(name));
+        id
+    };
+
+    // A connection that breaks, or sends something that isn't UTF-8, ends like a QUIT: the
+    // player still has to be removed.
+    let mut result = Ok(());
+    for line in reader.lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(err) => {
+                result = Err(err);
+                break;
+            }
+        };
+        let mut state = state.lock().unwrap();
+        match line.parse::<ClientMessage>() {
+            Ok(ClientMessage::Name(name)) => {
+                if let Some(player) = state.players.get_mut(&id) {
+                    player.name = name.clone();
+                }
+                state.broadcast(&ServerMessage::Joined(name));
+            }
+            Ok(ClientMessage::Guess(guess)) => handle_guess(&mut state, id, &guess),
+            Ok(ClientMessage::Quit) => break,
+            Err(err) => state.send(id, &ServerMessage::Error(err)),
+        }
+    }
+
+    let mut state = state.lock().unwrap();
+    if let Some(player) = state.players.remove(&id) {
+        state.broadcast(&ServerMessage::Left(player.name));
+    }
+    result
+}
+
+fn handle_guess(state: &mut State, id: u64, guess: &str) {
+    let range = state.range.clone();
+    let guess = match input::parse_guess(guess, &range) {
+        Ok(guess) => guess,
+        Err(err) => return state.send(id, &ServerMessage::Error(err.to_string())),
+    };
+    let Some(player) = state.players.get_mut(&id) else {
+        return;
+    };
+
+    let reply = match player.game.guess(guess) {
+        Ok(Ordering::Less) => ServerMessage::TooSmall(guess),
+        Ok(Ordering::Greater) => ServerMessage::TooBig(guess),
+        Ok(Ordering::Equal) => ServerMessage::Correct(guess),
+        Err(err) => ServerMessage::Error(err.to_string()),
+    };
+    state.send(id, &reply);
+
+    if reply == ServerMessage::Correct(guess) {
+        let winner = ServerMessage::Winner {
+            name: state.players[&id].name.clone(),
+            attempts: state.players[&id].game.attempts(),
+        };
+        state.broadcast(&winner);
+        state.new_round();
+        let round = ServerMessage::Round {
+            round: state.round,
+            min: *state.range.start(),
+            max: *state.range.end(),
+        };
+        state.broadcast(&round);
+    }
+}
+
+// ========== Client ==========
+pub struct Client {
+    stream: TcpStream,
+    reader: BufReader<TcpStream>,
+}
+
+impl Client {
+    pub fn connect(addr: impl ToSocketAddrs) -> io::Result<Client> {
+        let stream = TcpStream::connect(addr)?;
+        let reader = BufReader::new(stream.try_clone()?);
+        Ok(Client { stream, reader })
+    }
+
+    pub fn send(&mut self, message: &ClientMessage) -> io::Result<()> {
+        writeln!(self.stream, "{message}")
+    }
+
+    // Blocks until the next message; None once the server closed the connection.
+    pub fn receive(&mut self) -> io::Result<Option<ServerMessage>> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        line.parse()
+            .map(Some)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    // A second handle on the same connection, so one thread can send while another receives.
+    // Only one of the two should receive: each has a buffer of its own.
+    pub fn try_clone(&self) -> io::Result<Client> {
+        let stream = self.stream.try_clone()?;
+        let reader = BufReader::new(stream.try_clone()?);
+        Ok(Client { stream, reader })
+    }
+}
@@ -0,0 +1,371 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::mem;
+use std::path::Path;
+
+use rand::{Rng, SeedableRng};
+use wasmi::core::TrapCode;
+use wasmi::{Caller, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, TypedFunc};
+
+use crate::bot::Bounds;
+use crate::i18n;
+use crate::seed::GameRng;
+
+// ========== Wasm bot plugins ==========
+/*
+Strategies don't have to be built in: anyone can write one in a language that compiles to
+WebAssembly and hand over the `.wasm` file. The `guessing_game_bot` crate in this workspace does
+the plumbing for bots written in Rust.
+
+A plugin runs in the wasmi interpreter, sandboxed:
+- It can only call the imports below, so no files, network or clock.
+- Every call into it gets `fuel` to spend (roughly one unit per instruction). A bot that runs out,
+  say in an endless loop, is stopped instead of hanging the tournament.
+- Its memory can't grow past `memory` bytes.
+A plugin that breaks any of these, or traps in any other way, gets a `PluginError`.
+
+The interface, version `ABI_VERSION`:
+- export `abi_version() -> i32`: the version the plugin was written for.
+- export `next_guess(low: i64, high: i64) -> i64`: the next guess, given the bounds the secret is
+  still within (like `Strategy::next_guess`).
+- export `new_game(low: i64, high: i64)` (optional): a new game starts on the range low..=high.
+- import `guessing_game.random(low: i64, high: i64) -> i64`: a random number from low to high,
+  from the seeded generator of the game, so random bots can be replayed too.
+ */
+pub const ABI_VERSION: i32 = 1;
+const IMPORT_MODULE: &str = "guessing_game";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    // The fuel for one call into the plugin.
+    pub fuel: u64,
+    // The largest the plugin's memory can get, in bytes.
+    pub memory: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Limits {
+        Limits {
+            fuel: 1_000_000,
+            memory: 16 << 20,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum PluginError {
+    Io(io::Error),
+    // Not a module, or not one with the interface above.
+    Invalid(String),
+    OutOfFuel,
+    OutOfMemory,
+    // Any other trap, e.g. `unreachable` or a division by zero.
+    Trap(String),
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PluginError::Io(err) => write!(f, "{err}"),
+            PluginError::Invalid(err) => {
+                f.write_str(&i18n::message("plugin-invalid", &[("error", err)]))
+            }
+            PluginError::OutOfFuel => f.write_str(&i18n::text("plugin-out-of-fuel")),
+            PluginError::OutOfMemory => f.write_str(&i18n::text("plugin-out-of-memory")),
+            PluginError::Trap(err) => f.write_str(&i18n::message("plugin-trap", &[("error", err)])),
+        }
+    }
+}
+
+impl Error for PluginError {}
+
+impl From<io::Error> for PluginError {
+    fn from(err: io::Error) -> PluginError {
+        PluginError::Io(err)
+    }
+}
+
+impl From<wasmi::Error> for PluginError {
+    fn from(err: wasmi::Error) -> PluginError {
+        match err.as_trap_code() {
+            Some(TrapCode::OutOfFuel) => PluginError::OutOfFuel,
+            Some(TrapCode::GrowthOperationLimited) => PluginError::OutOfMemory,
+            _ => PluginError::Trap(err.to_string()),
+        }
+    }
+}
+
+// What the plugin's store holds: the generator behind `random`, and the memory limit.
+struct Sandbox {
+    rng: GameRng,
+    limits: StoreLimits,
+}
+
+pub struct Plugin {
+    name: String,
+    limits: Limits,
+    store: Store<Sandbox>,
+    next_guess: TypedFunc<(i64, i64), i64>,
+    new_game: Option<TypedFunc<(i64, i64), ()>>,
+}
+
+impl Plugin {
+    // The plugin is named after its file, `bots/halver.wasm` is `halver`.
+    pub fn load(path: &Path, limits: Limits) -> Result<Plugin, PluginError> {
+        let wasm = fs::read(path)?;
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        Plugin::new(name, &wasm, limits)
+    }
+
+    pub fn new(name: String, wasm: &[u8], limits: Limits) -> Result<Plugin, PluginError> {
+        let mut config = wasmi::Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, wasm).map_err(invalid)?;
+
+        let sandbox = Sandbox {
+            rng: GameRng::seed_from_u64(0),
+            limits: StoreLimitsBuilder::new()
+                .memory_size(limits.memory)
+                .trap_on_grow_failure(true)
+                .build(),
+        };
+        let mut store = Store::new(&engine, sandbox);
+        store.limiter(|sandbox| &mut sandbox.limits);
+
+        let mut linker = Linker::new(&engine);
+        linker
+            .func_wrap(
+                IMPORT_MODULE,
+                "random",
+                |mut caller: Caller<'_, Sandbox>, low: i64, high: i64| {
+                    if low > high {
+                        return Err(wasmi::Error::new(i18n::message(
+                            "plugin-random-reversed",
+                            &[("low", &low), ("high", &high)],
+                        )));
+                    }
+                    Ok(caller.data_mut().rng.gen_range(low..=high))
+                },
+            )
+            .map_err(invalid)?;
+
+        // The start function, if there is one, runs on fuel too.
+        store.set_fuel(limits.fuel).map_err(invalid)?;
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .and_then(|pre| pre.start(&mut store))
+            .map_err(|err| match PluginError::from(err) {
+                PluginError::Trap(err) => PluginError::Invalid(err),
+                err => err,
+            })?;
+
+        let abi_version = instance
+            .get_typed_func::<(), i32>(&store, "abi_version")
+            .map_err(|_| missing_export("abi_version"))?;
+        let next_guess = instance
+            .get_typed_func::<(i64, i64), i64>(&store, "next_guess")
+            .map_err(|_| missing_export("next_guess"))?;
+        // `new_game` may be left out, but if it's there it has to have the right type.
+        let new_game = match instance.get_func(&store, "new_game") {
+            Some(func) => Some(func.typed::<(i64, i64), ()>(&store).map_err(invalid)?),
+            None => None,
+        };
+
+        let mut plugin = Plugin {
+            name,
+            limits,
+            store,
+            next_guess,
+            new_game,
+        };
+        let version = plugin.call(|store| abi_version.call(store, ()))?;
+        if version != ABI_VERSION {
+            return Err(PluginError::Invalid(i18n::message(
+                "plugin-wrong-version",
+                &[("version", &version), ("expected", &ABI_VERSION)],
+            )));
+        }
+        Ok(plugin)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn new_game(&mut self, low: i64, high: i64) -> Result<(), PluginError> {
+        match self.new_game {
+            Some(new_game) => self.call(|store| new_game.call(store, (low, high))),
+            None => Ok(()),
+        }
+    }
+
+    // `rng` is lent to the plugin for `random` during the call.
+    pub fn next_guess(&mut self, bounds: &Bounds, rng: &mut GameRng) -> Result<i64, PluginError> {
+        let next_guess = self.next_guess;
+        mem::swap(&mut self.store.data_mut().rng, rng);
+        let guess = self.call(|store| next_guess.call(store, (bounds.low, bounds.high)));
+        mem::swap(&mut self.store.data_mut().rng, rng);
+        guess
+    }
+
+    // Every call starts with a full tank.
+    fn call<T>(
+        &mut self,
+        call: impl FnOnce(&mut Store<Sandbox>) -> Result<T, wasmi::Error>,
+    ) -> Result<T, PluginError> {
+        self.store.set_fuel(self.limits.fuel).map_err(invalid)?;
+        Ok(call(&mut self.store)?)
+    }
+}
+
+fn invalid(err: impl fmt::Display) -> PluginError {
+    PluginError::Invalid(err.to_string())
+}
+
+fn missing_export(name: &str) -> PluginError {
+    PluginError::Invalid(i18n::message("plugin-missing-export", &[("name", &name)]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seed;
+
+    // A module with the interface above, and `next_guess` returning what `body` leaves.
+    fn bot(version: i32, body: &str) -> Vec<u8> {
+        wat::parse_str(format!(
+            r#"(module
+                (import "guessing_game" "random" (func $random (param i64 i64) (result i64)))
+                (memory 1)
+                (func (export "abi_version") (result i32) (i32.const {version}))
+                (func (export "next_guess") (param $low i64) (param $high i64) (result i64)
+                    {body}))"#
+        ))
+        .unwrap()
+    }
+
+    const HALVER: &str = "(i64.add (local.get $low)
+        (i64.div_s (i64.sub (local.get $high) (local.get $low)) (i64.const 2)))";
+
+    fn load(wasm: &[u8], limits: Limits) -> Result<Plugin, PluginError> {
+        Plugin::new("test".to_string(), wasm, limits)
+    }
+
+    fn next_guess(plugin: &mut Plugin, low: i64, high: i64) -> Result<i64, PluginError> {
+        plugin.next_guess(&Bounds::new(&(low..=high)), &mut seed::rng_from_seed(1))
+    }
+
+    #[test]
+    fn a_valid_bot_plays() {
+        let mut plugin = load(&bot(ABI_VERSION, HALVER), Limits::default()).unwrap();
+        assert_eq!(plugin.name(), "test");
+        plugin.new_game(1, 100).unwrap();
+        assert_eq!(next_guess(&mut plugin, 1, 100).unwrap(), 50);
+        assert_eq!(next_guess(&mut plugin, 51, 100).unwrap(), 75);
+    }
+
+    #[test]
+    fn random_draws_from_the_game() {
+        let wasm = bot(
+            ABI_VERSION,
+            "(call $random (local.get $low) (local.get $high))",
+        );
+        let mut plugin = load(&wasm, Limits::default()).unwrap();
+        let mut rng = seed::rng_from_seed(1);
+        let expected: i64 = seed::rng_from_seed(1).gen_range(1..=100);
+        let bounds = Bounds::new(&(1..=100));
+        assert_eq!(plugin.next_guess(&bounds, &mut rng).unwrap(), expected);
+        // The generator was lent, and moved on.
+        assert_ne!(rng, seed::rng_from_seed(1));
+
+        let wasm = bot(
+            ABI_VERSION,
+            "(call $random (local.get $high) (local.get $low))",
+        );
+        let mut plugin = load(&wasm, Limits::default()).unwrap();
+        assert!(matches!(
+            next_guess(&mut plugin, 1, 100),
+            Err(PluginError::Trap(_))
+        ));
+    }
+
+    #[test]
+    fn an_endless_loop_runs_out_of_fuel() {
+        let wasm = bot(ABI_VERSION, "(loop $forever (br $forever)) (i64.const 0)");
+        let mut plugin = load(&wasm, Limits::default()).unwrap();
+        assert!(matches!(
+            next_guess(&mut plugin, 1, 100),
+            Err(PluginError::OutOfFuel)
+        ));
+        // The next call gets a full tank again, and loops again.
+        assert!(matches!(
+            next_guess(&mut plugin, 1, 100),
+            Err(PluginError::OutOfFuel)
+        ));
+    }
+
+    #[test]
+    fn memory_only_grows_to_the_limit() {
+        let limits = Limits {
+            memory: 2 << 16,
+            ..Limits::default()
+        };
+        let grow = |pages: i32| {
+            let body = format!("(i64.extend_i32_s (memory.grow (i32.const {pages})))");
+            load(&bot(ABI_VERSION, &body), limits).unwrap()
+        };
+        // From one page to two.
+        assert_eq!(next_guess(&mut grow(1), 1, 100).unwrap(), 1);
+        assert!(matches!(
+            next_guess(&mut grow(2), 1, 100),
+            Err(PluginError::OutOfMemory)
+        ));
+    }
+
+    #[test]
+    fn only_bots_of_this_version_load() {
+        let err = load(&bot(ABI_VERSION + 1, HALVER), Limits::default()).err();
+        assert!(
+            matches!(&err, Some(PluginError::Invalid(message)) if message.contains("version 2")),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn bots_need_the_whole_interface() {
+        let invalid = |wat: &str| match load(&wat::parse_str(wat).unwrap(), Limits::default()) {
+            Err(PluginError::Invalid(message)) => message,
+            Err(err) => panic!("expected an invalid bot, got {err:?}"),
+            Ok(_) => panic!("expected an invalid bot"),
+        };
+        assert_eq!(
+            invalid(r#"(module (func (export "abi_version") (result i32) (i32.const 1)))"#),
+            "no `next_guess` export"
+        );
+        assert_eq!(
+            invalid(
+                r#"(module (func (export "next_guess") (param i64 i64) (result i64) (i64.const 1)))"#
+            ),
+            "no `abi_version` export"
+        );
+        // `new_game` is optional, but not with another type.
+        let wrong_new_game = r#"(module
+            (func (export "abi_version") (result i32) (i32.const 1))
+            (func (export "next_guess") (param i64 i64) (result i64) (i64.const 1))
+            (func (export "new_game") (param i32)))"#;
+        invalid(wrong_new_game);
+        // Imports other than `random` aren't there.
+        invalid(r#"(module (import "wasi" "fd_write" (func (param i32))))"#);
+
+        assert!(matches!(
+            load(b"not wasm", Limits::default()),
+            Err(PluginError::Invalid(_))
+        ));
+    }
+}
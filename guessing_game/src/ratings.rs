@@ -0,0 +1,598 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+use std::io;
+use std::path::Path;
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::game::{Game, Outcome};
+use crate::i18n;
+use crate::storage;
+use crate::transcript::Header;
+
+// ========== Ratings ==========
+/*
+Players, and the bots of a `tournament`, get a Glicko-2 rating from the games they play on the
+same round: the same seed, range, host, secrets and limits, so the same secret. Of two games on a
+round the one that was won beats the one that was lost, and otherwise the one with fewer attempts
+wins; the same number of attempts is a draw.
+
+Glicko-2 is Mark Glickman's, see "Example of the Glicko-2 system" (2012) for the steps below. A
+rating comes with a deviation, how sure it is: new players start at 1500 ± 350, and the deviation
+shrinks with every game. The volatility is how much a rating is expected to move.
+
+Everything is kept in `ratings.json` in the data dir (with the same locking as the leaderboard,
+see storage.rs), including every change, so `ratings --name <name>` can show how a rating got
+where it is.
+- A game on a round somebody else played before is rated against each of their results at once,
+  as one rating period; each of them gets one game against the new result in the same period.
+  A player only counts once per round: playing it again, knowing the secret, isn't rated. Neither
+  is a resumed game, its seed is gone.
+- A tournament is one rating period for all of its bots, with every game of every match. Bots are
+  rated as `bot:<name>`, so they never mix with players.
+ */
+pub const FILE_NAME: &str = "ratings.json";
+// The system constant: how much the volatility may change. Glickman suggests 0.3 to 1.2.
+const TAU: f64 = 0.5;
+// Between the Glicko scale (1500 ± 350) and the Glicko-2 scale (0 ± 2.01).
+const SCALE: f64 = 173.7178;
+// How close the new volatility has to get.
+const EPSILON: f64 = 0.000_001;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rating {
+    pub rating: f64,
+    pub deviation: f64,
+    pub volatility: f64,
+}
+
+impl Default for Rating {
+    fn default() -> Rating {
+        Rating {
+            rating: 1500.0,
+            deviation: 350.0,
+            volatility: 0.06,
+        }
+    }
+}
+
+impl Rating {
+    // The rating after one rating period: `games` has the rating of the opponent before the
+    // period, and the score against them (1 for a win, 0.5 for a draw, 0 for a loss).
+    pub fn update(&self, games: &[(Rating, f64)]) -> Rating {
+        // Step 2: to the Glicko-2 scale.
+        let mu = (self.rating - 1500.0) / SCALE;
+        let phi = self.deviation / SCALE;
+
+        // Without games only the deviation grows.
+        if games.is_empty() {
+            let phi = (phi * phi + self.volatility * self.volatility).sqrt();
+            return Rating {
+                deviation: phi * SCALE,
+                ..*self
+            };
+        }
+
+        // Steps 3 and 4: the estimated variance, and the improvement over what was expected.
+        let mut information = 0.0;
+        let mut improvement = 0.0;
+        for (opponent, score) in games {
+            let mu_j = (opponent.rating - 1500.0) / SCALE;
+            let phi_j = opponent.deviation / SCALE;
+            let g = 1.0 / (1.0 + 3.0 * phi_j * phi_j / (PI * PI)).sqrt();
+            let expected = 1.0 / (1.0 + (-g * (mu - mu_j)).exp());
+            information += g * g * expected * (1.0 - expected);
+            improvement += g * (score - expected);
+        }
+        let v = 1.0 / information;
+        let delta = v * improvement;
+
+        // Steps 5 to 8: the new volatility, deviation and rating, back on the Glicko scale.
+        let volatility = volatility(delta, phi, v, self.volatility);
+        let phi_star = (phi * phi + volatility * volatility).sqrt();
+        let phi = 1.0 / (1.0 / (phi_star * phi_star) + 1.0 / v).sqrt();
+        let mu = mu + phi * phi * improvement;
+        Rating {
+            rating: mu * SCALE + 1500.0,
+            deviation: phi * SCALE,
+            volatility,
+        }
+    }
+}
+
+// Step 5: the new volatility is the root of `f`, found with the Illinois algorithm.
+fn volatility(delta: f64, phi: f64, v: f64, sigma: f64) -> f64 {
+    let a = (sigma * sigma).ln();
+    let f = |x: f64| {
+        let ex = x.exp();
+        ex * (delta * delta - phi * phi - v - ex) / (2.0 * (phi * phi + v + ex).powi(2))
+            - (x - a) / (TAU * TAU)
+    };
+
+    let mut low = a;
+    let mut high = if delta * delta > phi * phi + v {
+        (delta * delta - phi * phi - v).ln()
+    } else {
+        let mut k = 1.0;
+        while f(a - k * TAU) < 0.0 {
+            k += 1.0;
+        }
+        a - k * TAU
+    };
+    let (mut f_low, mut f_high) = (f(low), f(high));
+    while (high - low).abs() > EPSILON {
+        let c = low + (low - high) * f_low / (f_high - f_low);
+        let f_c = f(c);
+        if f_c * f_high <= 0.0 {
+            low = high;
+            f_low = f_high;
+        } else {
+            f_low /= 2.0;
+        }
+        high = c;
+        f_high = f_c;
+    }
+    (low / 2.0).exp()
+}
+
+// Less means a win: 1 point. Equal is half a point, Greater none.
+pub fn score(ordering: Ordering) -> f64 {
+    match ordering {
+        Ordering::Less => 1.0,
+        Ordering::Equal => 0.5,
+        Ordering::Greater => 0.0,
+    }
+}
+
+// How a tournament bot is rated.
+pub fn bot(name: &str) -> String {
+    format!("bot:{name}")
+}
+
+// What makes a round: everything that decides the secret, and the limits it's played with.
+pub fn round(header: &Header) -> String {
+    let mut round = format!(
+        "seed {}, {} to {}, {} host, {} secrets",
+        header.seed,
+        header.range.start(),
+        header.range.end(),
+        header.host,
+        header.secrets
+    );
+    if let Some(max) = header.max_attempts {
+        round.push_str(&format!(", {max} attempts"));
+    }
+    if let Some(limit) = header.time_limit {
+        round.push_str(&format!(", {}s", limit.as_secs_f64()));
+    }
+    round
+}
+
+// Every round of a speed run has its own secret: the n-th one drawn from the seed. So a speed run
+// round is only rated against the same round of other runs with the same seed.
+pub fn speed_run_round(header: &Header, number: u32, rounds: u32) -> String {
+    format!("{}, speed run round {number} of {rounds}", round(header))
+}
+
+// ========== Stored ratings ==========
+// One game of a rating period, from the side of `player`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Score {
+    pub player: String,
+    pub opponent: String,
+    pub score: f64,
+}
+
+// How a player did on a round.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub name: String,
+    pub won: bool,
+    pub attempts: u32,
+}
+
+impl Entry {
+    // None for a game that is still running.
+    pub fn from_game(name: &str, game: &Game) -> Option<Entry> {
+        let won = match game.outcome() {
+            Outcome::InProgress => return None,
+            Outcome::Won { .. } => true,
+            Outcome::Lost { .. } => false,
+        };
+        Some(Entry {
+            name: name.to_string(),
+            won,
+            attempts: game.attempts(),
+        })
+    }
+
+    // Less when `self` did better, like `tournament` compares games.
+    fn compare(&self, other: &Entry) -> Ordering {
+        (!self.won, self.attempts).cmp(&(!other.won, other.attempts))
+    }
+}
+
+// A rating period of one player.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Change {
+    pub name: String,
+    pub date: DateTime<Utc>,
+    // The round, or the tournament, that was rated.
+    pub event: String,
+    pub games: u32,
+    pub before: f64,
+    pub after: f64,
+    pub deviation: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Ratings {
+    pub players: BTreeMap<String, Rating>,
+    // Oldest first.
+    pub history: Vec<Change>,
+    // Who played each round so far, by `round`.
+    pub rounds: BTreeMap<String, Vec<Entry>>,
+}
+
+impl Ratings {
+    pub fn load(data_dir: &Path) -> io::Result<Ratings> {
+        storage::load(&data_dir.join(FILE_NAME))
+    }
+
+    // Rates `entry` against everybody who played `round` before, and returns the change of its
+    // player. None when there was nobody to rate against, or when they played the round before.
+    pub fn record(data_dir: &Path, round: &str, entry: Entry) -> io::Result<Option<Change>> {
+        storage::update(&data_dir.join(FILE_NAME), |ratings: &mut Ratings| {
+            let entries = ratings.rounds.entry(round.to_string()).or_default();
+            if entries.iter().any(|other| other.name == entry.name) {
+                return None;
+            }
+            let mut scores = Vec::new();
+            for other in entries.iter() {
+                let score = score(entry.compare(other));
+                scores.push(Score {
+                    player: entry.name.clone(),
+                    opponent: other.name.clone(),
+                    score,
+                });
+                scores.push(Score {
+                    player: other.name.clone(),
+                    opponent: entry.name.clone(),
+                    score: 1.0 - score,
+                });
+            }
+            let name = entry.name.clone();
+            entries.push(entry);
+
+            ratings
+                .rate(&scores, round)
+                .into_iter()
+                .find(|change| change.name == name)
+        })
+    }
+
+    // Like `rate`, for a whole tournament.
+    pub fn record_tournament(
+        data_dir: &Path,
+        scores: &[Score],
+        event: &str,
+    ) -> io::Result<Vec<Change>> {
+        storage::update(&data_dir.join(FILE_NAME), |ratings: &mut Ratings| {
+            ratings.rate(scores, event)
+        })
+    }
+
+    // One rating period: everybody in `scores` is updated at once, against the ratings from
+    // before it. Returns the changes, by name.
+    pub fn rate(&mut self, scores: &[Score], event: &str) -> Vec<Change> {
+        let rating = |name: &str| self.players.get(name).copied().unwrap_or_default();
+        let mut games: BTreeMap<&str, Vec<(Rating, f64)>> = BTreeMap::new();
+        for score in scores {
+            games
+                .entry(&score.player)
+                .or_default()
+                .push((rating(&score.opponent), score.score));
+        }
+
+        let date = Utc::now();
+        let changes: Vec<(Rating, Change)> = games
+            .into_iter()
+            .map(|(name, games)| {
+                let before = rating(name);
+                let after = before.update(&games);
+                let change = Change {
+                    name: name.to_string(),
+                    date,
+                    event: event.to_string(),
+                    games: games.len() as u32,
+                    before: before.rating,
+                    after: after.rating,
+                    deviation: after.deviation,
+                };
+                (after, change)
+            })
+            .collect();
+
+        changes
+            .into_iter()
+            .map(|(rating, change)| {
+                self.players.insert(change.name.clone(), rating);
+                self.history.push(change.clone());
+                change
+            })
+            .collect()
+    }
+
+    // Highest rating first, at most `top` of them.
+    pub fn top(&self, top: usize) -> Vec<(&String, &Rating)> {
+        let mut players: Vec<(&String, &Rating)> = self.players.iter().collect();
+        players.sort_by(|a, b| b.1.rating.total_cmp(&a.1.rating));
+        players.truncate(top);
+        players
+    }
+
+    pub fn history(&self, name: &str) -> Vec<&Change> {
+        self.history
+            .iter()
+            .filter(|change| change.name == name)
+            .collect()
+    }
+
+    pub fn games(&self, name: &str) -> u32 {
+        self.history(name).iter().map(|change| change.games).sum()
+    }
+}
+
+pub fn render(ratings: &Ratings, players: &[(&String, &Rating)]) -> String {
+    let [name, rating, deviation, games] = [
+        i18n::text("ratings-name"),
+        i18n::text("ratings-rating"),
+        i18n::text("ratings-deviation"),
+        i18n::text("ratings-games"),
+    ];
+    // Every column is as wide as its header in the language it's in, and at least as wide
+    // as its values.
+    let column = |header: &str, values: usize| values.max(header.chars().count());
+    let longest_name = players
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let width = column(&name, longest_name);
+    let rating_width = column(&rating, 6);
+    let deviation_width = column(&deviation, 4);
+    let games_width = column(&games, 5);
+
+    let mut table = format!(
+        "{:>4}  {name:<width$}  {rating:>rating_width$}  {deviation:>deviation_width$}  \
+         {games:>games_width$}\n",
+        "#"
+    );
+    for (rank, (name, rating)) in players.iter().enumerate() {
+        table.push_str(&format!(
+            "{:>4}  {:<width$}  {:>rating_width$.0}  {:>deviation_width$.0}  {:>games_width$}\n",
+            rank + 1,
+            name,
+            rating.rating,
+            rating.deviation,
+            ratings.games(name)
+        ));
+    }
+    table
+}
+
+// Oldest first: when, the rating before and after, and what was rated.
+pub fn render_history(history: &[&Change]) -> String {
+    let mut table = String::new();
+    for change in history {
+        table.push_str(&format!(
+            "{}  {:>4.0} -> {:>4.0}  ({:>+4.0})  {}\n",
+            change.date.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+            change.before,
+            change.after,
+            change.after - change.before,
+            change.event
+        ));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::{env, fs, process};
+
+    use super::*;
+    use crate::host::Host;
+    use crate::secrets::Secrets;
+
+    fn rating(rating: f64, deviation: f64) -> Rating {
+        Rating {
+            rating,
+            deviation,
+            ..Rating::default()
+        }
+    }
+
+    fn assert_close(got: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (got - expected).abs() <= tolerance,
+            "got {got}, expected {expected} ± {tolerance}"
+        );
+    }
+
+    fn data_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("guessing_game-ratings-{}-{name}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn entry(name: &str, won: bool, attempts: u32) -> Entry {
+        Entry {
+            name: name.to_string(),
+            won,
+            attempts,
+        }
+    }
+
+    fn score(player: &str, opponent: &str, score: f64) -> Score {
+        Score {
+            player: player.to_string(),
+            opponent: opponent.to_string(),
+            score,
+        }
+    }
+
+    #[test]
+    fn glickmans_example() {
+        let games = [
+            (rating(1400.0, 30.0), 1.0),
+            (rating(1550.0, 100.0), 0.0),
+            (rating(1700.0, 300.0), 0.0),
+        ];
+        let after = rating(1500.0, 200.0).update(&games);
+        assert_close(after.rating, 1464.05, 0.01);
+        assert_close(after.deviation, 151.52, 0.01);
+        assert_close(after.volatility, 0.059996, 0.000_001);
+    }
+
+    #[test]
+    fn a_period_without_games_only_adds_doubt() {
+        let before = rating(1500.0, 200.0);
+        let after = before.update(&[]);
+        assert_eq!(after.rating, 1500.0);
+        assert_eq!(after.volatility, before.volatility);
+        // Glickman's step 6, on the Glicko-2 scale: sqrt(φ² + σ²).
+        let phi = 200.0 / SCALE;
+        assert_close(
+            after.deviation,
+            (phi * phi + 0.06f64.powi(2)).sqrt() * SCALE,
+            1e-9,
+        );
+    }
+
+    #[test]
+    fn a_round_is_rated_once_per_player() {
+        let dir = data_dir("round");
+        let round = "seed 1, 1 to 100, fair host, uniform secrets";
+
+        // Nobody to rate against yet.
+        assert_eq!(
+            Ratings::record(&dir, round, entry("ada", true, 5)).unwrap(),
+            None
+        );
+        let change = Ratings::record(&dir, round, entry("bob", true, 7))
+            .unwrap()
+            .unwrap();
+        assert_eq!((change.name.as_str(), change.games), ("bob", 1));
+        assert!(change.after < change.before);
+
+        // Playing the round again, knowing the secret, changes nothing.
+        let ratings = Ratings::load(&dir).unwrap();
+        assert_eq!(
+            Ratings::record(&dir, round, entry("ada", true, 1)).unwrap(),
+            None
+        );
+        assert_eq!(Ratings::load(&dir).unwrap(), ratings);
+
+        assert!(ratings.players["ada"].rating > 1500.0);
+        assert_eq!(ratings.history.len(), 2);
+        assert_eq!(ratings.games("ada"), 1);
+        assert_eq!(
+            ratings.rounds[round],
+            [entry("ada", true, 5), entry("bob", true, 7)]
+        );
+
+        // A third player is rated against both, in one period; both of them get one game.
+        let change = Ratings::record(&dir, round, entry("cy", false, 10))
+            .unwrap()
+            .unwrap();
+        assert_eq!(change.games, 2);
+        let ratings = Ratings::load(&dir).unwrap();
+        assert_eq!(ratings.games("ada"), 2);
+        assert_eq!(ratings.games("bob"), 2);
+        assert_eq!(ratings.history.len(), 5);
+
+        // Another round starts over.
+        assert_eq!(
+            Ratings::record(&dir, "seed 2", entry("ada", true, 5)).unwrap(),
+            None
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_tournament_is_one_rating_period() {
+        let dir = data_dir("tournament");
+        let scores = [
+            score("bot:a", "bot:b", 1.0),
+            score("bot:b", "bot:a", 0.0),
+            score("bot:b", "bot:c", 1.0),
+            score("bot:c", "bot:b", 0.0),
+        ];
+        let changes = Ratings::record_tournament(&dir, &scores, "tournament").unwrap();
+        let names: Vec<&str> = changes.iter().map(|change| change.name.as_str()).collect();
+        assert_eq!(names, ["bot:a", "bot:b", "bot:c"]);
+
+        // Everybody is rated against the ratings from before the tournament: `b` won once and
+        // lost once against equal opponents, so it stays where it was, and `a` gains what `c`
+        // loses.
+        let [a, b, c] = &changes[..] else {
+            unreachable!()
+        };
+        assert_eq!((a.games, b.games, c.games), (1, 2, 1));
+        assert_close(b.after, 1500.0, 1e-9);
+        assert_close(a.after - 1500.0, 1500.0 - c.after, 1e-9);
+        assert!(a.after > 1500.0);
+        assert!(changes.iter().all(|change| change.event == "tournament"));
+
+        let ratings = Ratings::load(&dir).unwrap();
+        assert_eq!(ratings.history, changes);
+        assert!(ratings.rounds.is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn the_table_has_a_row_per_player() {
+        let mut ratings = Ratings::default();
+        ratings.rate(
+            &[score("ada", "bob", 1.0), score("bob", "ada", 0.0)],
+            "round",
+        );
+        let table = render(&ratings, &ratings.top(10));
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "   #  Name  Rating    RD  Games");
+        assert!(
+            lines[1].starts_with("   1  ada     1662   290      1"),
+            "{table}"
+        );
+        assert!(
+            lines[2].starts_with("   2  bob     1338   290      1"),
+            "{table}"
+        );
+    }
+
+    #[test]
+    fn speed_run_rounds_are_rated_apart() {
+        let header = Header {
+            seed: 7,
+            range: 1..=100,
+            host: Host::Fair,
+            secrets: Secrets::Uniform,
+            max_attempts: Some(10),
+            time_limit: None,
+        };
+        assert_eq!(
+            round(&header),
+            "seed 7, 1 to 100, fair host, uniform secrets, 10 attempts"
+        );
+        let first = speed_run_round(&header, 1, 3);
+        assert_eq!(first, format!("{}, speed run round 1 of 3", round(&header)));
+        assert_ne!(first, speed_run_round(&header, 2, 3));
+        assert_ne!(first, speed_run_round(&header, 1, 5));
+    }
+}
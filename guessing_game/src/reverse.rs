@@ -0,0 +1,291 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::bot::Bounds;
+use crate::i18n;
+
+// ========== Reverse mode ==========
+/*
+The roles flipped: the player picks a number and the program guesses it.
+The player answers every guess with higher, lower or correct, which is just the `Ordering` again
+(the guess compared to the secret: "higher" means the guess was `Less` than the secret).
+
+The program always guesses the middle of the bounds, so it needs at most ceil(log2(range size)) guesses.
+That also means a single wrong answer (a typo, or cheating) eventually leaves no number at all.
+When that happens the two answers that can't both be true are reported.
+ */
+
+// The player's answer to a guess, with the number of the turn it was given on (starting at 1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Answer {
+    pub turn: usize,
+    pub guess: i64,
+    pub ordering: Ordering,
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let answer = i18n::text(match self.ordering {
+            Ordering::Less => "answer-higher",
+            Ordering::Greater => "answer-lower",
+            Ordering::Equal => "answer-correct",
+        });
+        let text = i18n::message(
+            "answer",
+            &[
+                ("turn", &self.turn),
+                ("guess", &self.guess),
+                ("answer", &answer),
+            ],
+        );
+        f.write_str(&text)
+    }
+}
+
+// Answers that leave no number the secret could be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conflict {
+    // Two answers that can't both be true.
+    Answers { higher: Answer, lower: Answer },
+    // "Lower" on the lowest number of the range.
+    BelowRange { lower: Answer, min: i64 },
+    // "Higher" on the highest number of the range.
+    AboveRange { higher: Answer, max: i64 },
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            Conflict::Answers { higher, lower } => i18n::message(
+                "conflict-answers",
+                &[
+                    ("higher", higher),
+                    ("lower", lower),
+                    ("above", &higher.guess),
+                    ("below", &lower.guess),
+                ],
+            ),
+            Conflict::BelowRange { lower, min } => {
+                i18n::message("conflict-below-range", &[("lower", lower), ("min", min)])
+            }
+            Conflict::AboveRange { higher, max } => {
+                i18n::message("conflict-above-range", &[("higher", higher), ("max", max)])
+            }
+        };
+        f.write_str(&text)
+    }
+}
+
+impl Error for Conflict {}
+
+#[derive(Debug, Clone)]
+pub struct Guesser {
+    range: RangeInclusive<i64>,
+    bounds: Bounds,
+    answers: Vec<Answer>,
+    found: Option<i64>,
+}
+
+impl Guesser {
+    pub fn new(range: &RangeInclusive<i64>) -> Guesser {
+        Guesser {
+            range: range.clone(),
+            bounds: Bounds::new(range),
+            answers: Vec::new(),
+            found: None,
+        }
+    }
+
+    pub fn next_guess(&self) -> i64 {
+        self.bounds.midpoint()
+    }
+
+    // Feed back the answer to `next_guess`. Fails when the answers so far contradict each other.
+    pub fn answer(&mut self, ordering: Ordering) -> Result<(), Conflict> {
+        let guess = self.next_guess();
+        self.answers.push(Answer {
+            turn: self.answers.len() + 1,
+            guess,
+            ordering,
+        });
+
+        // "Higher" on the highest possible number (or "lower" on the lowest) leaves nothing.
+        let impossible = match ordering {
+            Ordering::Less => guess >= self.bounds.high,
+            Ordering::Greater => guess <= self.bounds.low,
+            Ordering::Equal => false,
+        };
+        if impossible {
+            return Err(self.conflict());
+        }
+
+        self.bounds.update(guess, ordering);
+        if ordering == Ordering::Equal {
+            self.found = Some(guess);
+        }
+        Ok(())
+    }
+
+    // The number, once the player answered correct.
+    pub fn found(&self) -> Option<i64> {
+        self.found
+    }
+
+    pub fn answers(&self) -> &[Answer] {
+        &self.answers
+    }
+
+    /*
+    The bounds are set by two answers: the highest guess answered "higher" and the lowest guess
+    answered "lower". Once they cross, those are the two answers that contradict each other.
+    When one side never got an answer, it's the edge of the range that the other answer went past.
+     */
+    fn conflict(&self) -> Conflict {
+        let higher = self
+            .answers
+            .iter()
+            .filter(|answer| answer.ordering == Ordering::Less)
+            .max_by_key(|answer| answer.guess)
+            .copied();
+        let lower = self
+            .answers
+            .iter()
+            .filter(|answer| answer.ordering == Ordering::Greater)
+            .min_by_key(|answer| answer.guess)
+            .copied();
+
+        match (higher, lower) {
+            (Some(higher), Some(lower)) => Conflict::Answers { higher, lower },
+            (None, Some(lower)) => Conflict::BelowRange {
+                lower,
+                min: *self.range.start(),
+            },
+            (Some(higher), None) => Conflict::AboveRange {
+                higher,
+                max: *self.range.end(),
+            },
+            (None, None) => unreachable!("a conflict needs at least one higher or lower answer"),
+        }
+    }
+}
+
+// What the player can type: h/higher/+, l/lower/- or c/correct/=.
+pub fn parse_answer(line: &str) -> Option<Ordering> {
+    match line.trim().to_lowercase().as_str() {
+        "h" | "higher" | "+" => Some(Ordering::Less),
+        "l" | "lower" | "-" => Some(Ordering::Greater),
+        "c" | "correct" | "=" => Some(Ordering::Equal),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Ordering::*;
+
+    // Plays the answers on 1..=100, and returns the guesses and the first conflict, if any.
+    fn answer_all(answers: &[Ordering]) -> (Vec<i64>, Result<(), Conflict>) {
+        let mut guesser = Guesser::new(&(1..=100));
+        let mut guesses = Vec::new();
+        for &ordering in answers {
+            guesses.push(guesser.next_guess());
+            if let Err(conflict) = guesser.answer(ordering) {
+                return (guesses, Err(conflict));
+            }
+        }
+        (guesses, Ok(()))
+    }
+
+    fn answer(turn: usize, guess: i64, ordering: Ordering) -> Answer {
+        Answer {
+            turn,
+            guess,
+            ordering,
+        }
+    }
+
+    #[test]
+    fn honest_answers_find_the_number() {
+        // The number is 60: higher, lower, lower, higher, higher, correct.
+        let mut guesser = Guesser::new(&(1..=100));
+        for ordering in [Less, Greater, Greater, Less, Less, Equal] {
+            assert_eq!(guesser.found(), None);
+            guesser.answer(ordering).unwrap();
+        }
+        assert_eq!(guesser.found(), Some(60));
+        let guesses: Vec<i64> = guesser.answers().iter().map(|a| a.guess).collect();
+        assert_eq!(guesses, [50, 75, 62, 56, 59, 60]);
+    }
+
+    #[test]
+    fn contradicting_answers_are_found() {
+        let (guesses, result) = answer_all(&[Less, Greater, Less, Less, Less, Less, Less]);
+        assert_eq!(guesses, [50, 75, 62, 68, 71, 73, 74]);
+        let conflict = Conflict::Answers {
+            higher: answer(7, 74, Less),
+            lower: answer(2, 75, Greater),
+        };
+        assert_eq!(result, Err(conflict));
+        assert_eq!(
+            conflict.to_string(),
+            "answer 7 (74: higher) conflicts with answer 2 (75: lower): \
+             your number would have to be above 74 and below 75"
+        );
+    }
+
+    #[test]
+    fn answers_past_the_range_are_found() {
+        let (guesses, result) = answer_all(&[Greater; 6]);
+        assert_eq!(guesses, [50, 25, 12, 6, 3, 1]);
+        assert_eq!(
+            result,
+            Err(Conflict::BelowRange {
+                lower: answer(6, 1, Greater),
+                min: 1
+            })
+        );
+
+        let (guesses, result) = answer_all(&[Less; 7]);
+        assert_eq!(guesses, [50, 75, 88, 94, 97, 99, 100]);
+        assert_eq!(
+            result,
+            Err(Conflict::AboveRange {
+                higher: answer(7, 100, Less),
+                max: 100
+            })
+        );
+
+        // On a range of one number, the first answer can already be wrong.
+        let mut guesser = Guesser::new(&(5..=5));
+        assert_eq!(
+            guesser.answer(Greater),
+            Err(Conflict::BelowRange {
+                lower: answer(1, 5, Greater),
+                min: 5
+            })
+        );
+    }
+
+    #[test]
+    fn answers_are_parsed() {
+        for (line, expected) in [
+            ("h", Some(Less)),
+            (" Higher ", Some(Less)),
+            ("+", Some(Less)),
+            ("l", Some(Greater)),
+            ("LOWER", Some(Greater)),
+            ("-", Some(Greater)),
+            ("c", Some(Equal)),
+            ("correct", Some(Equal)),
+            ("=", Some(Equal)),
+            ("", None),
+            ("yes", None),
+            ("50", None),
+        ] {
+            assert_eq!(parse_answer(line), expected, "{line:?}");
+        }
+    }
+}
@@ -0,0 +1,348 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use fs2::FileExt;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::difficulty::Difficulty;
+use crate::game::Game;
+use crate::hints;
+use crate::host::{Host, Secret};
+use crate::i18n;
+use crate::storage;
+
+// ========== Save and resume ==========
+/*
+Typing `:save` during a game writes it to a file, `:quit` does the same and stops.
+`--resume <file>` picks the game up again: same secret, same guesses, and the clock continues
+where it was.
+
+A save file is JSON, so it can be read (and edited) by anyone. Two things keep that from being a
+way to cheat:
+- The secret is never written as is. It's XOR-ed with a pad derived from a random nonce and a
+  secret key, so the file alone doesn't give it away.
+- The whole file is signed with an HMAC-SHA256 over the same key. Changing anything (the secret,
+  the guesses, the time) breaks the signature, and the file is refused.
+The key is 32 random bytes in `save.key` in the data dir, created the first time a game is saved.
+Only its owner can read it (on Unix), so a save file can only be resumed by the same user on the
+same machine. It's created under the same lock as the leaderboard: two games saving for the first
+time at once get the same key.
+
+An evil host never picked a secret, so there's nothing to hide: it's rebuilt by replaying the guesses.
+ */
+pub const DEFAULT_FILE_NAME: &str = "saved_game.json";
+const KEY_FILE_NAME: &str = "save.key";
+const VERSION: u32 = 1;
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SaveFile {
+    version: u32,
+    nonce: String,
+    difficulty: Difficulty,
+    min: i64,
+    max: i64,
+    max_attempts: Option<u32>,
+    #[serde(default)]
+    time_limit_ms: Option<u64>,
+    evil: bool,
+    // Hex of the secret XOR the pad; empty for an evil host.
+    secret: String,
+    guesses: Vec<i64>,
+    // The names of the hints taken, in order, so their cost survives a resume.
+    #[serde(default)]
+    hints: Vec<String>,
+    elapsed_ms: u64,
+    // Hex of the HMAC over this file, with `mac` itself left empty.
+    mac: String,
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    // Not a save file, or one from an incompatible version.
+    Format(String),
+    // The signature doesn't match: the file was changed, or saved with another key.
+    Tampered,
+    // Finished games can't be saved.
+    Finished,
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::Io(err) => write!(f, "{err}"),
+            SaveError::Format(err) => {
+                f.write_str(&i18n::message("save-invalid", &[("error", err)]))
+            }
+            SaveError::Tampered => f.write_str(&i18n::text("save-tampered")),
+            SaveError::Finished => f.write_str(&i18n::text("game-finished")),
+        }
+    }
+}
+
+impl Error for SaveError {}
+
+impl From<io::Error> for SaveError {
+    fn from(err: io::Error) -> SaveError {
+        SaveError::Io(err)
+    }
+}
+
+// The key used to hide and sign save files. Created in `data_dir` the first time it's needed.
+pub fn key(data_dir: &Path) -> io::Result<Vec<u8>> {
+    let path = data_dir.join(KEY_FILE_NAME);
+    let lock = storage::lock_file(&path)?;
+    lock.lock_exclusive()?;
+    let key = read_or_create_key(&path);
+    lock.unlock()?;
+    key
+}
+
+fn read_or_create_key(path: &Path) -> io::Result<Vec<u8>> {
+    match fs::read_to_string(path) {
+        Ok(hex) => from_hex(hex.trim())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid save key")),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            let mut key = vec![0; 32];
+            rand::thread_rng().fill_bytes(&mut key);
+            // Private from the start: there's no moment where others could read it.
+            let mut options = OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            options.mode(0o600);
+            options.open(path)?.write_all(to_hex(&key).as_bytes())?;
+            Ok(key)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+pub fn default_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(DEFAULT_FILE_NAME)
+}
+
+pub fn save(path: &Path, game: &Game, difficulty: Difficulty, key: &[u8]) -> Result<(), SaveError> {
+    if game.is_finished() {
+        return Err(SaveError::Finished);
+    }
+
+    let mut nonce = [0; 16];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let nonce = to_hex(&nonce);
+    let evil = game.host() == Host::Evil;
+    let secret = if evil {
+        String::new()
+    } else {
+        to_hex(&(game.secret() as u64 ^ pad(key, &nonce)).to_be_bytes())
+    };
+
+    let mut file = SaveFile {
+        version: VERSION,
+        nonce,
+        difficulty,
+        min: *game.range().start(),
+        max: *game.range().end(),
+        max_attempts: game.max_attempts(),
+        time_limit_ms: game.time_limit().map(|limit| limit.as_millis() as u64),
+        evil,
+        secret,
+        guesses: game.history().iter().map(|(guess, _)| *guess).collect(),
+        hints: game
+            .hints()
+            .iter()
+            .map(|(name, _, _)| name.clone())
+            .collect(),
+        elapsed_ms: game.elapsed().as_millis() as u64,
+        mac: String::new(),
+    };
+    file.mac = to_hex(&sign(key, &file)?);
+
+    let json = serde_json::to_string_pretty(&file).map_err(io::Error::from)?;
+    fs::write(path, json)?;
+    Ok(())
+}
+
+// Returns the game as it was saved, and the difficulty it was played on.
+pub fn load(path: &Path, key: &[u8]) -> Result<(Game, Difficulty), SaveError> {
+    let json = fs::read_to_string(path)?;
+    let mut file: SaveFile =
+        serde_json::from_str(&json).map_err(|err| SaveError::Format(err.to_string()))?;
+    if file.version != VERSION {
+        return Err(SaveError::Format(i18n::message(
+            "save-unknown-version",
+            &[("version", &file.version)],
+        )));
+    }
+
+    // Check the signature before trusting anything in the file.
+    let mac = from_hex(&std::mem::take(&mut file.mac)).ok_or(SaveError::Tampered)?;
+    let mut hmac = new_hmac(key);
+    hmac.update(&serde_json::to_vec(&file).map_err(io::Error::from)?);
+    hmac.verify_slice(&mac).map_err(|_| SaveError::Tampered)?;
+
+    let range = file.min..=file.max;
+    let secret = if file.evil {
+        Secret::Lazy {
+            low: file.min,
+            high: file.max,
+        }
+    } else {
+        let bytes: [u8; 8] = from_hex(&file.secret)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(SaveError::Tampered)?;
+        Secret::Fixed((u64::from_be_bytes(bytes) ^ pad(key, &file.nonce)) as i64)
+    };
+    // A valid signature means we wrote these values, but better safe than a panic in `Game::new`.
+    if let Secret::Fixed(secret) = secret {
+        if !range.contains(&secret) {
+            return Err(SaveError::Tampered);
+        }
+    }
+
+    // Replaying the guesses brings the game (and an evil host) back to where it was.
+    // The hints are taken again after the guesses; that gives the same total cost.
+    let mut game = Game::with_secret(range, secret)
+        .with_max_attempts(file.max_attempts)
+        .with_time_limit(file.time_limit_ms.map(Duration::from_millis));
+    for guess in &file.guesses {
+        game.guess(*guess)
+            .map_err(|err| SaveError::Format(err.to_string()))?;
+    }
+    for name in &file.hints {
+        let hint = hints::hint(name)
+            .ok_or_else(|| SaveError::Format(i18n::message("hint-unknown", &[("name", name)])))?;
+        game.hint(hint.as_ref())
+            .map_err(|err| SaveError::Format(err.to_string()))?;
+    }
+    if game.is_finished() {
+        return Err(SaveError::Finished);
+    }
+
+    Ok((
+        game.with_elapsed(Duration::from_millis(file.elapsed_ms)),
+        file.difficulty,
+    ))
+}
+
+fn new_hmac(key: &[u8]) -> HmacSha256 {
+    HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length")
+}
+
+fn sign(key: &[u8], file: &SaveFile) -> io::Result<Vec<u8>> {
+    let mut hmac = new_hmac(key);
+    hmac.update(&serde_json::to_vec(file)?);
+    Ok(hmac.finalize().into_bytes().to_vec())
+}
+
+// The 64 bit pad the secret is XOR-ed with: the first 8 bytes of HMAC(key, "secret" + nonce).
+fn pad(key: &[u8], nonce: &str) -> u64 {
+    let mut hmac = new_hmac(key);
+    hmac.update(b"secret");
+    hmac.update(nonce.as_bytes());
+    let bytes = hmac.finalize().into_bytes();
+    u64::from_be_bytes(bytes[..8].try_into().expect("a SHA-256 MAC has 32 bytes"))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::process;
+    use std::thread;
+
+    use super::*;
+
+    // A fresh directory for one test.
+    fn data_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("guessing_game-save-{}-{name}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn the_key_is_created_once() {
+        let dir = data_dir("once");
+        let key = super::key(&dir).unwrap();
+        assert_eq!(key.len(), 32);
+        assert_eq!(super::key(&dir).unwrap(), key);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn only_the_owner_can_read_the_key() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = data_dir("private");
+        super::key(&dir).unwrap();
+        let mode = fs::metadata(dir.join(KEY_FILE_NAME))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn games_saving_at_once_share_the_key() {
+        let dir = data_dir("race");
+        let keys: Vec<Vec<u8>> = thread::scope(|scope| {
+            let threads: Vec<_> = (0..8)
+                .map(|_| scope.spawn(|| super::key(&dir).unwrap()))
+                .collect();
+            threads.into_iter().map(|t| t.join().unwrap()).collect()
+        });
+        assert!(keys.iter().all(|key| *key == keys[0]));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn a_saved_game_resumes_and_a_changed_one_does_not() {
+        let dir = data_dir("resume");
+        let key = super::key(&dir).unwrap();
+        let path = default_path(&dir);
+
+        let mut game = Game::new(1..=100, 42).with_max_attempts(Some(7));
+        game.guess(50).unwrap();
+        save(&path, &game, Difficulty::Normal, &key).unwrap();
+
+        let (resumed, difficulty) = load(&path, &key).unwrap();
+        assert_eq!(difficulty, Difficulty::Normal);
+        assert_eq!(resumed.secret(), 42);
+        assert_eq!(resumed.history(), game.history());
+        assert_eq!(resumed.max_attempts(), Some(7));
+
+        let json = fs::read_to_string(&path).unwrap();
+        fs::write(
+            &path,
+            json.replace("\"max_attempts\": 7", "\"max_attempts\": 70"),
+        )
+        .unwrap();
+        assert!(matches!(load(&path, &key), Err(SaveError::Tampered)));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
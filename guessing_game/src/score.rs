@@ -0,0 +1,23 @@
+use std::time::Duration;
+
+// ========== Scoring ==========
+/*
+A score rewards three things:
+- A bigger range: finding a number between 1 and 10_000 is harder than between 1 and 10.
+  The range is worth 100 points per bit, so 1..=100 (~6.6 bits) is worth ~664 points.
+- Few attempts: binary search needs ceil(log2(range size)) attempts. Taking that many (or fewer,
+  with some luck) keeps the full score; every attempt beyond it scales the score down.
+- Speed: the score halves after a minute, and keeps shrinking the longer it takes.
+
+Losing a game always scores 0, so that is handled by the caller (see `Game::score`).
+ */
+pub fn score(attempts: u32, range_size: u128, elapsed: Duration) -> u32 {
+    // Even a range of 1 number is worth something, so never go below 1 bit.
+    let bits = (range_size as f64).log2().max(1.0);
+    let optimal_attempts = bits.ceil();
+
+    let attempt_factor = (optimal_attempts / attempts.max(1) as f64).min(1.0);
+    let time_factor = 60.0 / (60.0 + elapsed.as_secs_f64());
+
+    (100.0 * bits * attempt_factor * time_factor).round() as u32
+}
@@ -0,0 +1,403 @@
+use std::fmt;
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use rand::{Rng, RngCore, SeedableRng};
+use sha2::{Digest, Sha256};
+
+use crate::i18n;
+use crate::seed::GameRng;
+
+// ========== Secret sources ==========
+/*
+Where a fair host gets its secret from. The original game drew it with `gen_range(1..=100)`, so every
+number was equally likely; `--secrets <source>` picks something else:
+
+- `uniform`: every number in the range equally likely (the default).
+- `edges`: numbers near the ends of the range are the most likely, the middle the least.
+- `middle`: the other way around, a triangle peaking in the middle.
+- `file:<path>`: the numbers in a file, one per line, in order (and from the top again after the
+  last one). Blank lines and lines starting with `#` are skipped.
+- `phrase:<text>`: uniform, but drawn from a generator seeded by the phrase instead of `--seed`,
+  so a phrase like `phrase:correct horse` is an easy way to share a game.
+
+Sources that need randomness take it from the game's seeded `GameRng`, so `--seed` replays them too.
+The evil host doesn't pick a secret at all and ignores the source.
+
+Only the modes that draw a secret for a player take `--secrets`: the plain game, `tui`, `hot-cold`,
+`speedrun` and `batch`. The others (and `--resume`, which has its secret already) refuse it.
+ */
+pub trait SecretSource {
+    fn name(&self) -> &str;
+
+    // A secret within `range`.
+    fn draw(&mut self, range: &RangeInclusive<i64>, rng: &mut dyn RngCore) -> i64;
+
+    // Whether the source can draw from `range` at all; checked once before the first game.
+    fn check(&self, _range: &RangeInclusive<i64>) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+pub struct Uniform;
+
+impl SecretSource for Uniform {
+    fn name(&self) -> &str {
+        "uniform"
+    }
+
+    fn draw(&mut self, range: &RangeInclusive<i64>, rng: &mut dyn RngCore) -> i64 {
+        rng.gen_range(range.clone())
+    }
+}
+
+/*
+The mean of two uniform draws is a triangle: the middle is the most likely, the ends the least.
+Folding that triangle at the middle (so the middle goes to the ends and the ends to the middle)
+gives a V instead, for `edges`.
+ */
+pub struct Middle;
+
+impl SecretSource for Middle {
+    fn name(&self) -> &str {
+        "middle"
+    }
+
+    fn draw(&mut self, range: &RangeInclusive<i64>, rng: &mut dyn RngCore) -> i64 {
+        triangle(range, rng) as i64
+    }
+}
+
+pub struct Edges;
+
+impl SecretSource for Edges {
+    fn name(&self) -> &str {
+        "edges"
+    }
+
+    fn draw(&mut self, range: &RangeInclusive<i64>, rng: &mut dyn RngCore) -> i64 {
+        let (low, high) = (*range.start() as i128, *range.end() as i128);
+        let middle = (low + high).div_euclid(2);
+        let t = triangle(range, rng);
+        let folded = if t <= middle {
+            low + (middle - t)
+        } else {
+            high - (t - middle - 1)
+        };
+        folded as i64
+    }
+}
+
+// In i128, so the sum of two draws can't overflow. Odd sums are rounded up or down at random,
+// otherwise the triangle would lean to the low side.
+fn triangle(range: &RangeInclusive<i64>, rng: &mut dyn RngCore) -> i128 {
+    let sum = rng.gen_range(range.clone()) as i128 + rng.gen_range(range.clone()) as i128;
+    let round_up = sum.rem_euclid(2) == 1 && rng.gen::<bool>();
+    sum.div_euclid(2) + round_up as i128
+}
+
+pub struct List {
+    name: String,
+    numbers: Vec<i64>,
+    next: usize,
+}
+
+impl List {
+    pub fn read(path: &Path) -> Result<List, String> {
+        let text = fs::read_to_string(path).map_err(|err| {
+            i18n::message(
+                "secrets-unreadable",
+                &[("path", &path.display()), ("error", &err)],
+            )
+        })?;
+        let mut numbers = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let secret = line.parse().map_err(|_| {
+                i18n::message(
+                    "secrets-not-a-number",
+                    &[
+                        ("path", &path.display()),
+                        ("line", &(number + 1)),
+                        ("text", &line),
+                    ],
+                )
+            })?;
+            numbers.push(secret);
+        }
+        if numbers.is_empty() {
+            return Err(i18n::message("secrets-empty", &[("path", &path.display())]));
+        }
+
+        Ok(List {
+            name: format!("file:{}", path.display()),
+            numbers,
+            next: 0,
+        })
+    }
+}
+
+impl SecretSource for List {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn draw(&mut self, _range: &RangeInclusive<i64>, _rng: &mut dyn RngCore) -> i64 {
+        let secret = self.numbers[self.next];
+        self.next = (self.next + 1) % self.numbers.len();
+        secret
+    }
+
+    fn check(&self, range: &RangeInclusive<i64>) -> Result<(), String> {
+        match self.numbers.iter().find(|secret| !range.contains(secret)) {
+            Some(secret) => Err(i18n::message(
+                "secrets-out-of-range",
+                &[
+                    ("secret", secret),
+                    ("source", &self.name),
+                    ("min", range.start()),
+                    ("max", range.end()),
+                ],
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+pub struct Phrase {
+    rng: GameRng,
+}
+
+impl Phrase {
+    // The SHA-256 of the phrase is exactly the 32 bytes a ChaCha generator is seeded with.
+    pub fn new(phrase: &str) -> Phrase {
+        let seed: [u8; 32] = Sha256::digest(phrase.as_bytes()).into();
+        Phrase {
+            rng: GameRng::from_seed(seed),
+        }
+    }
+}
+
+impl SecretSource for Phrase {
+    fn name(&self) -> &str {
+        "phrase"
+    }
+
+    fn draw(&mut self, range: &RangeInclusive<i64>, _rng: &mut dyn RngCore) -> i64 {
+        self.rng.gen_range(range.clone())
+    }
+}
+
+// What `--secrets` asked for. The file of `file:<path>` is only read by `source`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Secrets {
+    #[default]
+    Uniform,
+    Edges,
+    Middle,
+    File(PathBuf),
+    Phrase(String),
+}
+
+impl Secrets {
+    pub fn source(&self) -> Result<Box<dyn SecretSource>, String> {
+        Ok(match self {
+            Secrets::Uniform => Box::new(Uniform),
+            Secrets::Edges => Box::new(Edges),
+            Secrets::Middle => Box::new(Middle),
+            Secrets::File(path) => Box::new(List::read(path)?),
+            Secrets::Phrase(phrase) => Box::new(Phrase::new(phrase)),
+        })
+    }
+}
+
+// The way it's written after `--secrets`.
+impl fmt::Display for Secrets {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Secrets::Uniform => f.write_str("uniform"),
+            Secrets::Edges => f.write_str("edges"),
+            Secrets::Middle => f.write_str("middle"),
+            Secrets::File(path) => write!(f, "file:{}", path.display()),
+            Secrets::Phrase(phrase) => write!(f, "phrase:{phrase}"),
+        }
+    }
+}
+
+impl FromStr for Secrets {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Secrets, String> {
+        if let Some(path) = s.strip_prefix("file:") {
+            return Ok(Secrets::File(PathBuf::from(path)));
+        }
+        if let Some(phrase) = s.strip_prefix("phrase:") {
+            return Ok(Secrets::Phrase(phrase.to_string()));
+        }
+        match s.to_lowercase().as_str() {
+            "uniform" => Ok(Secrets::Uniform),
+            "edges" => Ok(Secrets::Edges),
+            "middle" => Ok(Secrets::Middle),
+            _ => Err(i18n::message("secrets-unknown", &[("name", &s)])),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::process;
+
+    use super::*;
+    use crate::seed;
+
+    const RANGE: RangeInclusive<i64> = 1..=10;
+    const DRAWS: usize = 100_000;
+    // The chi-square value with 9 degrees of freedom that's exceeded by chance once in 1000.
+    const CHI_SQUARE_9: f64 = 27.88;
+
+    // How often each number of `RANGE` was drawn, from a fixed seed.
+    fn histogram(source: &mut dyn SecretSource) -> Vec<f64> {
+        let mut rng = seed::rng_from_seed(42);
+        let mut counts = vec![0.0; 10];
+        for _ in 0..DRAWS {
+            let secret = source.draw(&RANGE, &mut rng);
+            assert!(RANGE.contains(&secret), "{secret} is outside the range");
+            counts[(secret - 1) as usize] += 1.0;
+        }
+        counts
+    }
+
+    fn chi_square(counts: &[f64], probabilities: &[f64]) -> f64 {
+        counts
+            .iter()
+            .zip(probabilities)
+            .map(|(count, p)| {
+                let expected = p * DRAWS as f64;
+                (count - expected).powi(2) / expected
+            })
+            .sum()
+    }
+
+    #[test]
+    fn uniform_draws_every_number_equally_often() {
+        let counts = histogram(&mut Uniform);
+        let chi_square = chi_square(&counts, &[0.1; 10]);
+        assert!(chi_square < CHI_SQUARE_9, "{chi_square} for {counts:?}");
+    }
+
+    // The mean of two uniform draws, worked out exactly: odd sums go up or down half of the time.
+    #[test]
+    fn middle_is_a_triangle() {
+        let mut probabilities = [0.0; 10];
+        for a in RANGE {
+            for b in RANGE {
+                let sum = a + b;
+                if sum % 2 == 0 {
+                    probabilities[(sum / 2 - 1) as usize] += 0.01;
+                } else {
+                    probabilities[(sum / 2 - 1) as usize] += 0.005;
+                    probabilities[(sum / 2) as usize] += 0.005;
+                }
+            }
+        }
+        let counts = histogram(&mut Middle);
+        let chi_square = chi_square(&counts, &probabilities);
+        assert!(chi_square < CHI_SQUARE_9, "{chi_square} for {counts:?}");
+        assert!(counts[4] > 5.0 * counts[0] && counts[5] > 5.0 * counts[9]);
+    }
+
+    #[test]
+    fn edges_is_a_v() {
+        let counts = histogram(&mut Edges);
+        // Falling towards the middle from both ends, ...
+        for i in 0..4 {
+            assert!(counts[i] > counts[i + 1], "{counts:?}");
+            assert!(counts[9 - i] > counts[8 - i], "{counts:?}");
+        }
+        // ... and the same on both sides, within what chance allows.
+        for i in 0..5 {
+            let (left, right) = (counts[i], counts[9 - i]);
+            assert!((left - right).abs() < 0.1 * left.max(right), "{counts:?}");
+        }
+        assert!(counts[0] > 5.0 * counts[4], "{counts:?}");
+    }
+
+    #[test]
+    fn edges_and_middle_work_on_the_full_i64_range() {
+        let range = i64::MIN..=i64::MAX;
+        let mut rng = seed::rng_from_seed(1);
+        for _ in 0..1000 {
+            Edges.draw(&range, &mut rng);
+            Middle.draw(&range, &mut rng);
+        }
+        assert_eq!(Edges.draw(&(5..=5), &mut rng), 5);
+        assert_eq!(Middle.draw(&(5..=5), &mut rng), 5);
+    }
+
+    #[test]
+    fn a_list_is_drawn_in_order_and_then_again() {
+        let path = env::temp_dir().join(format!("guessing_game-secrets-{}", process::id()));
+        fs::write(&path, "# secrets\n3\n\n  7 \n1\n").unwrap();
+        let mut list = List::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let mut rng = seed::rng_from_seed(0);
+        let drawn: Vec<i64> = (0..7).map(|_| list.draw(&RANGE, &mut rng)).collect();
+        assert_eq!(drawn, [3, 7, 1, 3, 7, 1, 3]);
+        assert_eq!(list.check(&RANGE), Ok(()));
+        assert_eq!(
+            list.check(&(1..=5)),
+            Err(format!("7 in {} is not between 1 and 5", list.name()))
+        );
+    }
+
+    #[test]
+    fn a_list_needs_whole_numbers() {
+        let path = env::temp_dir().join(format!("guessing_game-secrets-bad-{}", process::id()));
+        fs::write(&path, "3\nseven\n").unwrap();
+        let err = List::read(&path).err().unwrap();
+        assert!(
+            err.ends_with("line 2: `seven` is not a whole number"),
+            "{err}"
+        );
+        fs::write(&path, "# nothing\n\n").unwrap();
+        let err = List::read(&path).err().unwrap();
+        assert!(err.ends_with("has no numbers"), "{err}");
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn a_phrase_draws_the_same_secrets_whatever_the_seed() {
+        let draw = |phrase: &str, seed: u64| -> Vec<i64> {
+            let mut source = Phrase::new(phrase);
+            let mut rng = seed::rng_from_seed(seed);
+            (0..20)
+                .map(|_| source.draw(&(1..=1000), &mut rng))
+                .collect()
+        };
+        assert_eq!(draw("correct horse", 1), draw("correct horse", 2));
+        assert_ne!(draw("correct horse", 1), draw("battery staple", 1));
+    }
+
+    #[test]
+    fn secrets_are_written_the_way_they_are_parsed() {
+        let all = [
+            Secrets::Uniform,
+            Secrets::Edges,
+            Secrets::Middle,
+            Secrets::File(PathBuf::from("/tmp/secrets.txt")),
+            Secrets::Phrase("correct horse: battery".to_string()),
+        ];
+        for secrets in all {
+            assert_eq!(secrets.to_string().parse(), Ok(secrets));
+        }
+        assert_eq!("EDGES".parse(), Ok(Secrets::Edges));
+        assert!("gaussian".parse::<Secrets>().is_err());
+    }
+}
@@ -0,0 +1,22 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+// ========== Seeded randomness ==========
+/*
+`rand::thread_rng()` is seeded by the OS, so a game can never be played the same way twice.
+To make sessions reproducible every game draws from a `GameRng` built from a plain u64 seed.
+With the same seed (and the same inputs) a session replays exactly.
+
+ChaCha8 is used instead of `StdRng` because rand doesn't promise that `StdRng` keeps producing
+the same numbers across versions; the ChaCha generators do.
+ */
+pub type GameRng = ChaCha8Rng;
+
+pub fn rng_from_seed(seed: u64) -> GameRng {
+    GameRng::seed_from_u64(seed)
+}
+
+// Used when no seed was given: pick one from the OS-seeded generator, so it can still be printed and reused.
+pub fn random_seed() -> u64 {
+    rand::thread_rng().gen()
+}
@@ -0,0 +1,180 @@
+use std::time::{Duration, Instant};
+
+use crate::game::{Game, Outcome};
+use crate::i18n;
+
+// ========== Speed run ==========
+/*
+A speed run is a number of rounds played back to back against one clock. Every finished round adds
+a split: how long that round took, and the total time so far. The clock keeps going in between
+rounds, so the total is the wall-clock time of the whole run, not just the sum of the rounds.
+
+A round that is lost (out of attempts, or out of time with `--time-limit`) still counts as played;
+the run goes on, and the split shows it was lost.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Split {
+    // Starting at 1.
+    pub round: u32,
+    pub attempts: u32,
+    pub won: bool,
+    // The time of this round.
+    pub time: Duration,
+    // The time since the start of the run.
+    pub total: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct SpeedRun {
+    rounds: u32,
+    splits: Vec<Split>,
+    started: Instant,
+}
+
+impl SpeedRun {
+    // The clock starts right away.
+    pub fn new(rounds: u32) -> SpeedRun {
+        SpeedRun {
+            rounds,
+            splits: Vec::new(),
+            started: Instant::now(),
+        }
+    }
+
+    // The number of the round being played, starting at 1.
+    pub fn round(&self) -> u32 {
+        self.splits.len() as u32 + 1
+    }
+
+    pub fn rounds(&self) -> u32 {
+        self.rounds
+    }
+
+    // Adds the split of a finished round.
+    pub fn record(&mut self, game: &Game) -> Split {
+        assert!(game.is_finished(), "only finished rounds have a split");
+        let split = Split {
+            round: self.round(),
+            attempts: game.attempts(),
+            won: matches!(game.outcome(), Outcome::Won { .. }),
+            time: game.elapsed(),
+            total: self.started.elapsed(),
+        };
+        self.splits.push(split);
+        split
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.round() > self.rounds
+    }
+
+    pub fn splits(&self) -> &[Split] {
+        &self.splits
+    }
+
+    // The time of the last split, or the running time while the run isn't finished.
+    pub fn total(&self) -> Duration {
+        match self.splits.last() {
+            Some(split) if self.is_finished() => split.total,
+            _ => self.started.elapsed(),
+        }
+    }
+}
+
+// A table with one row per split, with the same layout as the leaderboard.
+pub fn render(splits: &[Split]) -> String {
+    let mut table = format!(
+        "{:>5}  {:>8}  {:>8}  {:>8}  {}\n",
+        i18n::text("split-round"),
+        i18n::text("split-attempts"),
+        i18n::text("split-time"),
+        i18n::text("split-total"),
+        i18n::text("split-result"),
+    );
+    for split in splits {
+        let result = if split.won {
+            i18n::text("split-won")
+        } else {
+            i18n::text("split-lost")
+        };
+        table.push_str(&format!(
+            "{:>5}  {:>8}  {:>7.1}s  {:>7.1}s  {result}\n",
+            split.round,
+            split.attempts,
+            split.time.as_secs_f64(),
+            split.total.as_secs_f64(),
+        ));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A finished round with its clock stopped at `seconds`.
+    fn round(guesses: &[i64], max_attempts: Option<u32>, seconds: u64) -> Game {
+        let mut game = Game::new(1..=100, 42)
+            .with_max_attempts(max_attempts)
+            .with_stopped_clock(Duration::from_secs(seconds));
+        for &guess in guesses {
+            game.guess(guess).unwrap();
+        }
+        assert!(game.is_finished());
+        game
+    }
+
+    #[test]
+    fn every_round_adds_a_split() {
+        let mut run = SpeedRun::new(3);
+        assert_eq!((run.round(), run.rounds()), (1, 3));
+
+        let split = run.record(&round(&[50, 25, 42], None, 7));
+        assert_eq!((split.round, split.attempts, split.won), (1, 3, true));
+        assert_eq!(split.time, Duration::from_secs(7));
+        assert_eq!(run.round(), 2);
+        assert!(!run.is_finished());
+
+        // A lost round still counts as played.
+        let split = run.record(&round(&[10, 20], Some(2), 4));
+        assert_eq!((split.round, split.attempts, split.won), (2, 2, false));
+        assert_eq!(split.time, Duration::from_secs(4));
+        assert!(!run.is_finished());
+
+        run.record(&round(&[42], None, 1));
+        assert!(run.is_finished());
+        assert_eq!(run.splits().len(), 3);
+        assert!(run
+            .splits()
+            .windows(2)
+            .all(|pair| pair[0].total <= pair[1].total));
+        assert_eq!(run.total(), run.splits()[2].total);
+    }
+
+    #[test]
+    #[should_panic(expected = "only finished rounds have a split")]
+    fn a_round_in_progress_has_no_split() {
+        SpeedRun::new(1).record(&Game::new(1..=100, 42));
+    }
+
+    #[test]
+    fn the_table_has_a_row_per_split() {
+        let split = |round, attempts, won, time, total| Split {
+            round,
+            attempts,
+            won,
+            time: Duration::from_millis(time),
+            total: Duration::from_millis(total),
+        };
+        let table = render(&[
+            split(1, 3, true, 7240, 7300),
+            split(2, 10, false, 12000, 19400),
+        ]);
+        assert_eq!(
+            table,
+            "Round  Attempts     Split     Total  Result\n\
+             \x20   1         3      7.2s      7.3s  won\n\
+             \x20   2        10     12.0s     19.4s  lost\n"
+        );
+    }
+}
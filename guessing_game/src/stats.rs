@@ -0,0 +1,322 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::difficulty::Difficulty;
+use crate::game::{Game, Outcome};
+use crate::{histogram, i18n, storage};
+
+// ========== Statistics ==========
+/*
+The leaderboard only keeps wins. For statistics every finished game counts, won or lost, so they
+are kept apart in `stats.json` in the data dir (with the same locking, see storage.rs).
+
+`stats` sums up the games of one player, optionally between two dates (`--from`, `--to`, inclusive,
+in local time): how many were played and won, the longest run of wins in a row, and the mean and
+median number of attempts with a histogram. Attempts are only counted for won games; a lost game
+says more about `--max-attempts` than about the player.
+ */
+pub const FILE_NAME: &str = "stats.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    pub name: String,
+    pub difficulty: Difficulty,
+    pub won: bool,
+    pub attempts: u32,
+    pub seconds: f64,
+    pub date: DateTime<Utc>,
+}
+
+impl Record {
+    // None for a game that is still running.
+    pub fn from_game(name: &str, difficulty: Difficulty, game: &Game) -> Option<Record> {
+        let won = match game.outcome() {
+            Outcome::InProgress => return None,
+            Outcome::Won { .. } => true,
+            Outcome::Lost { .. } => false,
+        };
+        Some(Record {
+            name: name.to_string(),
+            difficulty,
+            won,
+            attempts: game.attempts(),
+            seconds: game.elapsed().as_secs_f64(),
+            date: Utc::now(),
+        })
+    }
+}
+
+// Oldest game first, as they were played.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    pub games: Vec<Record>,
+}
+
+// What `stats` looks at: the games of `name` between `from` and `to`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Filter {
+    pub name: String,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl Filter {
+    pub fn matches(&self, record: &Record) -> bool {
+        let day = record.date.with_timezone(&Local).date_naive();
+        record.name == self.name
+            && self.from.is_none_or(|from| day >= from)
+            && self.to.is_none_or(|to| day <= to)
+    }
+}
+
+impl Stats {
+    pub fn load(data_dir: &Path) -> io::Result<Stats> {
+        storage::load(&data_dir.join(FILE_NAME))
+    }
+
+    pub fn record(data_dir: &Path, record: Record) -> io::Result<()> {
+        storage::update(&data_dir.join(FILE_NAME), |stats: &mut Stats| {
+            stats.games.push(record)
+        })
+    }
+
+    pub fn summary(&self, filter: &Filter) -> Summary {
+        let games: Vec<&Record> = self
+            .games
+            .iter()
+            .filter(|record| filter.matches(record))
+            .collect();
+        Summary::of(&games)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Summary {
+    pub games: u32,
+    pub wins: u32,
+    pub best_streak: u32,
+    // Only over won games; None without any.
+    pub mean_attempts: Option<f64>,
+    pub median_attempts: Option<f64>,
+    // The number of won games per number of attempts.
+    pub distribution: BTreeMap<u32, u32>,
+}
+
+impl Summary {
+    // `games` in the order they were played, for the streaks.
+    pub fn of(games: &[&Record]) -> Summary {
+        let mut summary = Summary {
+            games: games.len() as u32,
+            ..Summary::default()
+        };
+
+        let mut streak = 0;
+        let mut attempts = Vec::new();
+        for record in games {
+            if record.won {
+                summary.wins += 1;
+                streak += 1;
+                summary.best_streak = summary.best_streak.max(streak);
+                attempts.push(record.attempts);
+                *summary.distribution.entry(record.attempts).or_insert(0) += 1;
+            } else {
+                streak = 0;
+            }
+        }
+
+        if !attempts.is_empty() {
+            attempts.sort_unstable();
+            let total: u64 = attempts.iter().map(|&a| a as u64).sum();
+            summary.mean_attempts = Some(total as f64 / attempts.len() as f64);
+            // With an even number of games the median is halfway between the middle two.
+            let middle = attempts.len() / 2;
+            summary.median_attempts = Some(if attempts.len().is_multiple_of(2) {
+                (attempts[middle - 1] as u64 + attempts[middle] as u64) as f64 / 2.0
+            } else {
+                attempts[middle] as f64
+            });
+        }
+        summary
+    }
+
+    // Percentage of the games that was won; None without any games.
+    pub fn win_rate(&self) -> Option<f64> {
+        (self.games > 0).then(|| self.wins as f64 * 100.0 / self.games as f64)
+    }
+}
+
+pub fn render(summary: &Summary) -> String {
+    let number = |value: Option<f64>| match value {
+        Some(value) => format!("{value:.1}"),
+        None => "-".to_string(),
+    };
+    let rows = [
+        ("stats-games", summary.games.to_string()),
+        ("stats-wins", summary.wins.to_string()),
+        ("stats-win-rate", format!("{}%", number(summary.win_rate()))),
+        ("stats-mean", number(summary.mean_attempts)),
+        ("stats-median", number(summary.median_attempts)),
+        ("stats-best-streak", summary.best_streak.to_string()),
+    ]
+    .map(|(id, value)| (i18n::text(id), value));
+    // Labels differ in length from one language to the next, so the values line up with the longest.
+    let width = rows
+        .iter()
+        .map(|(label, _)| label.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut text = String::new();
+    for (label, value) in &rows {
+        text.push_str(&format!("{label:<width$}  {value}\n"));
+    }
+    if !summary.distribution.is_empty() {
+        text.push('\n');
+        text.push_str(&i18n::text("stats-histogram"));
+        text.push('\n');
+        text.push_str(&histogram::render(&summary.distribution));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    // A game of `name` on the given day of May 2024, at `hour` local time.
+    fn game(name: &str, won: bool, attempts: u32, day: u32, hour: u32) -> Record {
+        Record {
+            name: name.to_string(),
+            difficulty: Difficulty::Normal,
+            won,
+            attempts,
+            seconds: 10.0,
+            date: Local
+                .with_ymd_and_hms(2024, 5, day, hour, 0, 0)
+                .unwrap()
+                .with_timezone(&Utc),
+        }
+    }
+
+    // Games of one player, all on the same day: (won, attempts).
+    fn summary(games: &[(bool, u32)]) -> Summary {
+        let records: Vec<Record> = games
+            .iter()
+            .map(|&(won, attempts)| game("ann", won, attempts, 1, 12))
+            .collect();
+        Summary::of(&records.iter().collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn the_best_streak_is_the_longest_run_of_wins() {
+        let won = (true, 5);
+        let lost = (false, 10);
+        assert_eq!(summary(&[]).best_streak, 0);
+        assert_eq!(summary(&[lost, lost]).best_streak, 0);
+        assert_eq!(
+            summary(&[won, won, lost, won, won, won, lost, won]).best_streak,
+            3
+        );
+        assert_eq!(summary(&[lost, won, won, won, won]).best_streak, 4);
+    }
+
+    #[test]
+    fn the_median_of_an_odd_number_of_wins_is_the_middle_one() {
+        let summary = summary(&[(true, 7), (true, 3), (false, 1), (true, 5)]);
+        assert_eq!(summary.median_attempts, Some(5.0));
+        assert_eq!(summary.mean_attempts, Some(5.0));
+    }
+
+    #[test]
+    fn the_median_of_an_even_number_of_wins_is_halfway() {
+        let even = summary(&[(true, 4), (true, 9), (true, 1), (true, 6)]);
+        assert_eq!(even.median_attempts, Some(5.0));
+        assert_eq!(even.mean_attempts, Some(5.0));
+
+        // The middle two don't have to fit in a u32 together.
+        let huge = summary(&[(true, u32::MAX), (true, u32::MAX)]);
+        assert_eq!(huge.median_attempts, Some(u32::MAX as f64));
+    }
+
+    #[test]
+    fn the_win_rate_counts_lost_games_too() {
+        let summary = summary(&[(true, 3), (false, 10), (true, 4), (true, 5)]);
+        assert_eq!((summary.games, summary.wins), (4, 3));
+        assert_eq!(summary.win_rate(), Some(75.0));
+
+        let nothing = Summary::of(&[]);
+        assert_eq!(nothing.win_rate(), None);
+        assert_eq!(nothing.mean_attempts, None);
+        assert_eq!(nothing.median_attempts, None);
+    }
+
+    #[test]
+    fn from_and_to_include_whole_local_days() {
+        let stats = Stats {
+            games: vec![
+                game("ann", true, 1, 1, 23),
+                game("ann", true, 2, 2, 0),
+                game("ann", true, 3, 3, 12),
+                game("bob", true, 4, 3, 12),
+                game("ann", true, 5, 4, 23),
+                game("ann", true, 6, 5, 0),
+            ],
+        };
+        let day = |day| NaiveDate::from_ymd_opt(2024, 5, day);
+        let attempts = |from, to| {
+            let filter = Filter {
+                name: "ann".to_string(),
+                from,
+                to,
+            };
+            let summary = stats.summary(&filter);
+            summary.distribution.into_keys().collect::<Vec<_>>()
+        };
+
+        assert_eq!(attempts(None, None), [1, 2, 3, 5, 6]);
+        assert_eq!(attempts(day(2), day(4)), [2, 3, 5]);
+        assert_eq!(attempts(day(3), day(3)), [3]);
+        assert_eq!(attempts(day(5), None), [6]);
+        assert_eq!(attempts(None, day(1)), [1]);
+    }
+
+    #[test]
+    fn render_shows_the_numbers_and_a_histogram() {
+        let summary = summary(&[(true, 3), (true, 3), (false, 10), (true, 5)]);
+        let bar = |length| "#".repeat(length);
+        assert_eq!(
+            render(&summary),
+            format!(
+                "Games played     4\n\
+                 Games won        3\n\
+                 Win rate         75.0%\n\
+                 Mean attempts    3.7\n\
+                 Median attempts  3.0\n\
+                 Best streak      2\n\
+                 \n\
+                 Attempts per won game:\n\
+                 \x20         3 | {} 2\n\
+                 \x20         4 | {:40} 0\n\
+                 \x20         5 | {:40} 1\n",
+                bar(40),
+                "",
+                bar(20)
+            )
+        );
+        assert_eq!(
+            render(&Summary::of(&[])),
+            "Games played     0\n\
+             Games won        0\n\
+             Win rate         -%\n\
+             Mean attempts    -\n\
+             Median attempts  -\n\
+             Best streak      0\n"
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use fs2::FileExt;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+// ========== Local storage ==========
+/*
+Everything the game remembers between sessions (like the leaderboard) is a JSON file in the user's
+data dir, e.g. `~/.local/share/guessing_game` on Linux. `--data-dir` points somewhere else instead.
+
+Two games can finish at the same moment, so every file is guarded by a lock file next to it:
+- `load` takes a shared lock, so it never sees a half-written file.
+- `update` takes an exclusive lock for the whole read-modify-write, so no update is lost.
+  The new contents go to a temporary file first, which is then renamed over the old one.
+  A rename is atomic, so even a crash mid-write can't leave a corrupt file behind.
+ */
+pub fn data_dir(custom: Option<&Path>) -> io::Result<PathBuf> {
+    match custom {
+        Some(dir) => Ok(dir.to_path_buf()),
+        None => dirs::data_dir()
+            .map(|dir| dir.join("guessing_game"))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory found")),
+    }
+}
+
+// A missing (or empty) file is not an error, it just means nothing was stored yet.
+pub fn load<T: DeserializeOwned + Default>(path: &Path) -> io::Result<T> {
+    let lock = lock_file(path)?;
+    lock.lock_shared()?;
+    let result = read(path);
+    lock.unlock()?;
+    result
+}
+
+// Runs `change` on the stored value while holding the exclusive lock, then writes the result back.
+pub fn update<T, R>(path: &Path, change: impl FnOnce(&mut T) -> R) -> io::Result<R>
+where
+    T: DeserializeOwned + Serialize + Default,
+{
+    let lock = lock_file(path)?;
+    lock.lock_exclusive()?;
+    let result = read(path).and_then(|mut value| {
+        let result = change(&mut value);
+        write(path, &value)?;
+        Ok(result)
+    });
+    lock.unlock()?;
+    result
+}
+
+// The lock guarding `path`, next to it. Also used for files that aren't JSON, like the save key.
+pub(crate) fn lock_file(path: &Path) -> io::Result<File> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path.with_extension("lock"))
+}
+
+fn read<T: DeserializeOwned + Default>(path: &Path) -> io::Result<T> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(T::default()),
+        Err(err) => return Err(err),
+    };
+    if contents.trim().is_empty() {
+        return Ok(T::default());
+    }
+    Ok(serde_json::from_str(&contents)?)
+}
+
+fn write<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(value)?)?;
+    fs::rename(tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Barrier};
+    use std::{env, process, thread};
+
+    use super::*;
+
+    fn data_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("guessing_game-storage-{}-{name}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn nothing_stored_is_the_default() {
+        let dir = data_dir("empty");
+        let path = dir.join("numbers.json");
+        assert_eq!(load::<Vec<u32>>(&path).unwrap(), Vec::<u32>::new());
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, "  \n").unwrap();
+        assert_eq!(load::<Vec<u32>>(&path).unwrap(), Vec::<u32>::new());
+        fs::write(&path, "[1, 2").unwrap();
+        assert!(load::<Vec<u32>>(&path).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn concurrent_updates_are_all_kept() {
+        const THREADS: u32 = 8;
+        const UPDATES: u32 = 25;
+        let dir = data_dir("concurrent");
+        let path = dir.join("numbers.json");
+        let barrier = Arc::new(Barrier::new(THREADS as usize + 1));
+
+        let writers: Vec<_> = (0..THREADS)
+            .map(|thread| {
+                let (path, barrier) = (path.clone(), Arc::clone(&barrier));
+                thread::spawn(move || {
+                    barrier.wait();
+                    for round in 0..UPDATES {
+                        let len = update(&path, |numbers: &mut Vec<u32>| {
+                            numbers.push(thread * UPDATES + round);
+                            numbers.len()
+                        })
+                        .unwrap();
+                        assert!(len > round as usize);
+                    }
+                })
+            })
+            .collect();
+        // Reading meanwhile never sees a half-written file.
+        let reader = {
+            let path = path.clone();
+            thread::spawn(move || {
+                barrier.wait();
+                for _ in 0..100 {
+                    load::<Vec<u32>>(&path).unwrap();
+                }
+            })
+        };
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        reader.join().unwrap();
+
+        let mut numbers: Vec<u32> = load(&path).unwrap();
+        numbers.sort_unstable();
+        assert_eq!(numbers, (0..THREADS * UPDATES).collect::<Vec<u32>>());
+        assert!(!path.with_extension("tmp").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,340 @@
+use std::cmp::Ordering;
+use std::ops::RangeInclusive;
+
+use crate::bot::Bounds;
+use crate::game::{Game, Outcome};
+use crate::host::Host;
+use crate::i18n;
+use crate::plugin::Plugin;
+use crate::ratings::{self, Score};
+use crate::seed::{self, GameRng};
+
+// ========== Tournament ==========
+/*
+A round robin between plugin bots: every bot plays one match against every other bot. A match is
+a number of games where both bots get the same secret (drawn from the seed, like `benchmark`),
+and each game goes to the bot that needed fewer attempts. The bot that won more games wins the
+match.
+
+The ranking is by mean attempts over every game a bot played, fewest first, with matches won as
+the tie breaker. A game is never endless: after `max_attempts` it's lost, and counts with all of
+those attempts.
+
+A bot that crashes, runs out of fuel or memory, or guesses outside the range is disqualified: it
+loses the match it was playing and all the ones after it, and ends up at the bottom.
+
+Every game is also a game for the ratings (see ratings.rs), including the one a bot was
+disqualified in, which it loses.
+ */
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 1_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Standing {
+    pub name: String,
+    pub won: u32,
+    pub drawn: u32,
+    pub lost: u32,
+    pub games: u32,
+    pub attempts: u64,
+    // Why the bot was disqualified, if it was.
+    pub disqualified: Option<String>,
+}
+
+impl Standing {
+    fn new(name: &str) -> Standing {
+        Standing {
+            name: name.to_string(),
+            won: 0,
+            drawn: 0,
+            lost: 0,
+            games: 0,
+            attempts: 0,
+            disqualified: None,
+        }
+    }
+
+    // None before the first game.
+    pub fn mean(&self) -> Option<f64> {
+        (self.games > 0).then(|| self.attempts as f64 / self.games as f64)
+    }
+}
+
+// Plays every match, and returns the standings from first to last, with the scores of every game
+// for the ratings.
+pub fn run(
+    bots: &mut [Plugin],
+    range: &RangeInclusive<i64>,
+    host: Host,
+    games: u32,
+    max_attempts: u32,
+    seed: u64,
+) -> (Vec<Standing>, Vec<Score>) {
+    let mut standings: Vec<Standing> = bots.iter().map(|bot| Standing::new(bot.name())).collect();
+    let mut scores = Vec::new();
+
+    for first in 0..bots.len() {
+        for second in first + 1..bots.len() {
+            // Every match starts from the same seed, so every bot gets the same secrets.
+            let mut secrets = seed::rng_from_seed(seed);
+            let mut rngs = [
+                seed::rng_from_seed(seed.wrapping_add(1)),
+                seed::rng_from_seed(seed.wrapping_add(1)),
+            ];
+            let mut wins = [0, 0];
+
+            for _ in 0..games {
+                let out = |index: usize| standings[index].disqualified.is_some();
+                if out(first) || out(second) {
+                    break;
+                }
+
+                let game = Game::hosted(range.clone(), host, &mut secrets)
+                    .with_max_attempts(Some(max_attempts));
+                let mut results = [None, None];
+                for (side, index) in [first, second].into_iter().enumerate() {
+                    match play(&mut bots[index], game.clone(), &mut rngs[side]) {
+                        Ok(game) => {
+                            standings[index].games += 1;
+                            standings[index].attempts += game.attempts() as u64;
+                            results[side] = Some(game);
+                        }
+                        Err(err) => standings[index].disqualified = Some(err),
+                    }
+                }
+                if let [Some(a), Some(b)] = &results {
+                    match compare(a, b) {
+                        Ordering::Less => wins[0] += 1,
+                        Ordering::Greater => wins[1] += 1,
+                        Ordering::Equal => {}
+                    }
+                }
+
+                let points = match &results {
+                    [Some(a), Some(b)] => {
+                        let points = ratings::score(compare(a, b));
+                        [points, 1.0 - points]
+                    }
+                    [Some(_), None] => [1.0, 0.0],
+                    [None, Some(_)] => [0.0, 1.0],
+                    [None, None] => [0.0, 0.0],
+                };
+                let names = [bots[first].name(), bots[second].name()].map(ratings::bot);
+                for side in 0..2 {
+                    scores.push(Score {
+                        player: names[side].clone(),
+                        opponent: names[1 - side].clone(),
+                        score: points[side],
+                    });
+                }
+            }
+
+            // A disqualified bot loses, whatever the score was; when both are, they both lose.
+            let result = match (
+                standings[first].disqualified.is_some(),
+                standings[second].disqualified.is_some(),
+            ) {
+                (false, false) => Some(wins[1].cmp(&wins[0])),
+                (false, true) => Some(Ordering::Less),
+                (true, false) => Some(Ordering::Greater),
+                (true, true) => None,
+            };
+            match result {
+                None => {
+                    standings[first].lost += 1;
+                    standings[second].lost += 1;
+                }
+                Some(Ordering::Less) => {
+                    standings[first].won += 1;
+                    standings[second].lost += 1;
+                }
+                Some(Ordering::Greater) => {
+                    standings[first].lost += 1;
+                    standings[second].won += 1;
+                }
+                Some(Ordering::Equal) => {
+                    standings[first].drawn += 1;
+                    standings[second].drawn += 1;
+                }
+            }
+        }
+    }
+
+    standings.sort_by(|a, b| {
+        let mean = |standing: &Standing| standing.mean().unwrap_or(f64::INFINITY);
+        a.disqualified
+            .is_some()
+            .cmp(&b.disqualified.is_some())
+            .then(mean(a).total_cmp(&mean(b)))
+            .then(b.won.cmp(&a.won))
+    });
+    (standings, scores)
+}
+
+// One game, with the same loop as `bot::play`. The error says why the bot is disqualified.
+fn play(bot: &mut Plugin, mut game: Game, rng: &mut GameRng) -> Result<Game, String> {
+    let range = game.range().clone();
+    bot.new_game(*range.start(), *range.end())
+        .map_err(|err| err.to_string())?;
+    let mut bounds = Bounds::new(&range);
+    while !game.is_finished() {
+        let guess = bot
+            .next_guess(&bounds, rng)
+            .map_err(|err| err.to_string())?;
+        let ordering = game.guess(guess).map_err(|err| {
+            i18n::message("tournament-guessed", &[("guess", &guess), ("error", &err)])
+        })?;
+        bounds.update(guess, ordering);
+    }
+    Ok(game)
+}
+
+// Less when `a` did better: won rather than lost, or in fewer attempts.
+fn compare(a: &Game, b: &Game) -> Ordering {
+    let key = |game: &Game| {
+        (
+            !matches!(game.outcome(), Outcome::Won { .. }),
+            game.attempts(),
+        )
+    };
+    key(a).cmp(&key(b))
+}
+
+// The standings as a table, in the same style as the leaderboard.
+pub fn render(standings: &[Standing]) -> String {
+    let [rank_header, bot, mean_header, won, drawn, lost] = [
+        i18n::text("tournament-rank"),
+        i18n::text("tournament-bot"),
+        i18n::text("tournament-mean"),
+        i18n::text("tournament-won"),
+        i18n::text("tournament-drawn"),
+        i18n::text("tournament-lost"),
+    ];
+    // Every column is as wide as its header in the language it's in, like in the ratings table.
+    let column = |header: &str, values: usize| values.max(header.chars().count());
+    let longest_name = standings
+        .iter()
+        .map(|standing| standing.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let rank_width = column(&rank_header, 4);
+    let width = column(&bot, longest_name);
+    let mean_width = column(&mean_header, 8);
+    let won_width = column(&won, 3);
+    let drawn_width = column(&drawn, 5);
+    let lost_width = column(&lost, 4);
+
+    let mut table = format!(
+        "{rank_header:>rank_width$}  {bot:<width$}  {mean_header:>mean_width$}  \
+         {won:>won_width$}  {drawn:>drawn_width$}  {lost:>lost_width$}\n"
+    );
+    for (rank, standing) in standings.iter().enumerate() {
+        let rank = match standing.disqualified {
+            Some(_) => "-".to_string(),
+            None => (rank + 1).to_string(),
+        };
+        let mean = match standing.mean() {
+            Some(mean) => format!("{mean:.2}"),
+            None => "-".to_string(),
+        };
+        table.push_str(&format!(
+            "{rank:>rank_width$}  {:<width$}  {mean:>mean_width$}  {:>won_width$}  \
+             {:>drawn_width$}  {:>lost_width$}",
+            standing.name, standing.won, standing.drawn, standing.lost
+        ));
+        if let Some(reason) = &standing.disqualified {
+            table.push_str("  ");
+            table.push_str(&i18n::message(
+                "tournament-disqualified",
+                &[("reason", reason)],
+            ));
+        }
+        table.push('\n');
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::Limits;
+
+    // A bot whose `next_guess` returns what `body` leaves.
+    fn bot(name: &str, body: &str) -> Plugin {
+        let wasm = wat::parse_str(format!(
+            r#"(module
+                (func (export "abi_version") (result i32) (i32.const 1))
+                (func (export "next_guess") (param $low i64) (param $high i64) (result i64)
+                    {body}))"#
+        ))
+        .unwrap();
+        Plugin::new(name.to_string(), &wasm, Limits::default()).unwrap()
+    }
+
+    fn bots() -> Vec<Plugin> {
+        vec![
+            // Out of the range right away.
+            bot("below", "(i64.sub (local.get $low) (i64.const 1))"),
+            bot("counter", "(local.get $low)"),
+            bot(
+                "halver",
+                "(i64.add (local.get $low)
+                    (i64.div_s (i64.sub (local.get $high) (local.get $low)) (i64.const 2)))",
+            ),
+        ]
+    }
+
+    #[test]
+    fn bots_are_ranked_and_disqualified() {
+        let (standings, scores) = run(&mut bots(), &(1..=100), Host::Fair, 5, 1_000, 3);
+        let names: Vec<&str> = standings.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["halver", "counter", "below"]);
+
+        let [halver, counter, below] = &standings[..] else {
+            unreachable!()
+        };
+        // Halving takes at most 7 guesses, counting up takes as many as the secret.
+        assert_eq!((halver.won, halver.lost), (2, 0));
+        assert_eq!((counter.won, counter.lost), (1, 1));
+        assert_eq!(halver.games, 5);
+        assert!(halver.attempts <= 7 * 5);
+        assert!(halver.mean() < counter.mean());
+
+        // Disqualified in its first game, so it lost every match and finished none of its games.
+        // Its opponent in that game still played it; the match after that never started.
+        assert_eq!(counter.games, 5 + 1);
+        assert_eq!(
+            (below.won, below.drawn, below.lost, below.games),
+            (0, 0, 2, 0)
+        );
+        assert_eq!(below.mean(), None);
+        // The reason is in the player's language, after the guess.
+        let reason = below.disqualified.as_deref().unwrap();
+        assert!(reason.starts_with("guessed 0: "), "{reason}");
+
+        // Five games between the two that stayed, and the one `below` lost.
+        assert_eq!(scores.len(), 2 * (5 + 1));
+        let against_below: Vec<(&str, f64)> = scores
+            .iter()
+            .filter(|score| score.opponent == "bot:below")
+            .map(|score| (score.player.as_str(), score.score))
+            .collect();
+        assert_eq!(against_below, [("bot:counter", 1.0)]);
+
+        let rendered = render(&standings);
+        assert!(rendered.starts_with("Rank  Bot"), "{rendered}");
+        assert!(rendered.contains("   -  below"), "{rendered}");
+        assert!(rendered.ends_with(&format!("disqualified: {reason}\n")));
+
+        // The columns grow with the headers of another language.
+        let dutch = i18n::in_language("nl", || render(&standings));
+        assert!(dutch.starts_with("Plaats  Bot"), "{dutch}");
+        assert!(dutch.contains("     -  below"), "{dutch}");
+        assert!(dutch.ends_with(&format!("gediskwalificeerd: {reason}\n")));
+    }
+
+    #[test]
+    fn the_same_seed_plays_the_same_tournament() {
+        let first = run(&mut bots(), &(1..=100), Host::Fair, 5, 1_000, 3);
+        assert_eq!(run(&mut bots(), &(1..=100), Host::Fair, 5, 1_000, 3), first);
+    }
+}
@@ -0,0 +1,593 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, LineWriter, Write};
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::batch;
+use crate::game::{Game, Outcome};
+use crate::host::Host;
+use crate::i18n;
+use crate::input;
+use crate::secrets::Secrets;
+use crate::seed;
+
+// ========== Transcripts ==========
+/*
+`--record <file>` writes down a game as it's played, and `replay <file>` plays it again on the
+engine and checks that every answer is still the same. A transcript of a game that once worked is
+a regression test for later changes to the engine.
+
+The format is plain text, one line per fact, so a transcript can be read and written by hand:
+
+    guessing-game transcript 1
+    seed 9
+    range 1 100
+    host fair
+    secrets uniform
+    max-attempts 7
+    time-limit-ms 30000
+    1520 input 50
+    1520 response greater
+    2810 input abc
+    2810 response not_a_number
+    4077 input 12
+    4077 response equal
+    4077 outcome won
+
+The first line gives the version; a transcript of another version is refused instead of being
+read wrong. Next comes what the game was set up with (`max-attempts` and `time-limit-ms` only
+when there was a limit), enough to draw the same secret again. After that come the events, each
+with the time on the game's clock in milliseconds:
+- `input`: a line the player typed, as it was typed (without surrounding whitespace).
+- `response`: what the engine made of the input before it: `less`, `greater` or `equal` for a
+  guess, or one of the error codes of batch mode (see `batch::error_code`). Every input is
+  followed by its response, an input without one doesn't replay.
+- `timeout`: the clock ran out while waiting for input.
+- `outcome`: `won` or `lost`, once the game is over. A game that was abandoned has none.
+
+Replaying sets the game's clock to the time of every event, so a time limit ends the game at the
+same point it did the first time. Commands like `:hint` don't change any answer and aren't written.
+ */
+pub const VERSION: u32 = 1;
+const MAGIC: &str = "guessing-game transcript";
+
+// What the game was set up with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub seed: u64,
+    pub range: RangeInclusive<i64>,
+    pub host: Host,
+    pub secrets: Secrets,
+    pub max_attempts: Option<u32>,
+    pub time_limit: Option<Duration>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    Input(String),
+    Response(String),
+    Timeout,
+    Outcome(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    // The line in the transcript, starting at 1.
+    pub line: usize,
+    // The time on the game's clock.
+    pub at: Duration,
+    pub event: Event,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transcript {
+    pub header: Header,
+    pub entries: Vec<Entry>,
+}
+
+#[derive(Debug)]
+pub enum TranscriptError {
+    Io(io::Error),
+    // Not a transcript, or one from an incompatible version.
+    Format {
+        line: usize,
+        message: String,
+    },
+    // The game can't be set up again, e.g. the file of `secrets file:<path>` is gone.
+    Setup(String),
+    // The engine answered differently than it did when the transcript was written.
+    Mismatch {
+        line: usize,
+        expected: String,
+        got: String,
+    },
+}
+
+impl fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TranscriptError::Io(err) => write!(f, "{err}"),
+            TranscriptError::Format { line, message } => f.write_str(&i18n::message(
+                "transcript-invalid",
+                &[("line", line), ("error", message)],
+            )),
+            TranscriptError::Setup(err) => {
+                f.write_str(&i18n::message("transcript-setup", &[("error", err)]))
+            }
+            TranscriptError::Mismatch {
+                line,
+                expected,
+                got,
+            } => f.write_str(&i18n::message(
+                "transcript-mismatch",
+                &[("line", line), ("expected", expected), ("got", got)],
+            )),
+        }
+    }
+}
+
+impl Error for TranscriptError {}
+
+impl From<io::Error> for TranscriptError {
+    fn from(err: io::Error) -> TranscriptError {
+        TranscriptError::Io(err)
+    }
+}
+
+// ========== Recording ==========
+// Every event is written (and flushed) right away, so a crashed game still leaves a transcript.
+pub struct Recorder {
+    file: LineWriter<File>,
+}
+
+impl Recorder {
+    pub fn create(path: &Path, header: &Header) -> io::Result<Recorder> {
+        let mut file = LineWriter::new(File::create(path)?);
+        writeln!(file, "{MAGIC} {VERSION}")?;
+        writeln!(file, "seed {}", header.seed)?;
+        writeln!(
+            file,
+            "range {} {}",
+            header.range.start(),
+            header.range.end()
+        )?;
+        writeln!(file, "host {}", header.host)?;
+        writeln!(file, "secrets {}", header.secrets)?;
+        if let Some(max) = header.max_attempts {
+            writeln!(file, "max-attempts {max}")?;
+        }
+        if let Some(limit) = header.time_limit {
+            writeln!(file, "time-limit-ms {}", limit.as_millis())?;
+        }
+        Ok(Recorder { file })
+    }
+
+    // `at` is the time on the game's clock, `game.elapsed()`.
+    pub fn record(&mut self, at: Duration, event: &Event) -> io::Result<()> {
+        let at = at.as_millis();
+        match event {
+            Event::Input(text) => writeln!(self.file, "{at} input {}", text.trim()),
+            Event::Response(code) => writeln!(self.file, "{at} response {code}"),
+            Event::Timeout => writeln!(self.file, "{at} timeout"),
+            Event::Outcome(code) => writeln!(self.file, "{at} outcome {code}"),
+        }
+    }
+}
+
+// ========== Reading ==========
+impl Transcript {
+    pub fn load(path: &Path) -> Result<Transcript, TranscriptError> {
+        Transcript::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<Transcript, TranscriptError> {
+        let mut lines = text.lines().enumerate().map(|(i, line)| (i + 1, line));
+        let error = |line: usize, message: String| TranscriptError::Format { line, message };
+
+        let (_, first) = lines
+            .next()
+            .ok_or_else(|| error(1, i18n::text("transcript-empty")))?;
+        let version = first
+            .strip_prefix(MAGIC)
+            .and_then(|version| version.trim().parse::<u32>().ok())
+            .ok_or_else(|| {
+                let magic = format!("{MAGIC} {VERSION}");
+                error(
+                    1,
+                    i18n::message("transcript-no-magic", &[("magic", &magic)]),
+                )
+            })?;
+        if version != VERSION {
+            let message = i18n::message("transcript-unknown-version", &[("version", &version)]);
+            return Err(error(1, message));
+        }
+
+        let mut seed = None;
+        let mut range = None;
+        let mut host = Host::default();
+        let mut secrets = Secrets::default();
+        let mut max_attempts = None;
+        let mut time_limit = None;
+        let mut entries = Vec::new();
+
+        for (number, line) in lines {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (word, rest) = line.split_once(' ').unwrap_or((line, ""));
+            let value = |what: &str| {
+                let message = i18n::message(
+                    "transcript-invalid-value",
+                    &[("setting", &what), ("value", &rest)],
+                );
+                error(number, message)
+            };
+
+            // Events start with their time; everything before the first event is the header.
+            if let Ok(at) = word.parse::<u64>() {
+                let (kind, payload) = rest.split_once(' ').unwrap_or((rest, ""));
+                let event = match kind {
+                    "input" => Event::Input(payload.to_string()),
+                    "response" => Event::Response(payload.to_string()),
+                    "timeout" => Event::Timeout,
+                    "outcome" => Event::Outcome(payload.to_string()),
+                    _ => {
+                        let message =
+                            i18n::message("transcript-unknown-event", &[("event", &kind)]);
+                        return Err(error(number, message));
+                    }
+                };
+                entries.push(Entry {
+                    line: number,
+                    at: Duration::from_millis(at),
+                    event,
+                });
+                continue;
+            }
+            if !entries.is_empty() {
+                let message = i18n::message("transcript-after-events", &[("setting", &word)]);
+                return Err(error(number, message));
+            }
+
+            match word {
+                "seed" => seed = Some(rest.parse().map_err(|_| value("seed"))?),
+                "range" => {
+                    let bounds: Vec<i64> = rest
+                        .split_whitespace()
+                        .map(|bound| bound.parse())
+                        .collect::<Result<_, _>>()
+                        .map_err(|_| value("range"))?;
+                    match bounds[..] {
+                        [min, max] if min <= max => range = Some(min..=max),
+                        _ => return Err(value("range")),
+                    }
+                }
+                "host" => host = rest.parse().map_err(|_| value("host"))?,
+                "secrets" => secrets = rest.parse().map_err(|_| value("secrets"))?,
+                "max-attempts" => {
+                    max_attempts = Some(rest.parse().map_err(|_| value("max-attempts"))?)
+                }
+                "time-limit-ms" => {
+                    let millis = rest.parse().map_err(|_| value("time-limit-ms"))?;
+                    time_limit = Some(Duration::from_millis(millis));
+                }
+                _ => {
+                    let message =
+                        i18n::message("transcript-unknown-setting", &[("setting", &word)]);
+                    return Err(error(number, message));
+                }
+            }
+        }
+
+        let missing = |setting: &str| {
+            error(
+                1,
+                i18n::message("transcript-missing", &[("setting", &setting)]),
+            )
+        };
+        Ok(Transcript {
+            header: Header {
+                seed: seed.ok_or_else(|| missing("seed"))?,
+                range: range.ok_or_else(|| missing("range"))?,
+                host,
+                secrets,
+                max_attempts,
+                time_limit,
+            },
+            entries,
+        })
+    }
+}
+
+// ========== Replaying ==========
+// Plays the transcript on a new game, and returns the number of inputs replayed.
+pub fn replay(transcript: &Transcript) -> Result<usize, TranscriptError> {
+    let header = &transcript.header;
+    let mut source = header.secrets.source().map_err(TranscriptError::Setup)?;
+    source
+        .check(&header.range)
+        .map_err(TranscriptError::Setup)?;
+    let mut rng = seed::rng_from_seed(header.seed);
+    let mut game = Game::sourced(header.range.clone(), header.host, source.as_mut(), &mut rng)
+        .with_max_attempts(header.max_attempts)
+        .with_time_limit(header.time_limit);
+
+    let mut inputs = 0;
+    // The line of the last input and the response to it, until the transcript says what it
+    // should have been. Every input needs a response before anything else happens.
+    let mut response: Option<(usize, &str)> = None;
+    let unanswered = |(line, got): (usize, &str)| TranscriptError::Mismatch {
+        line,
+        expected: i18n::text("transcript-no-response"),
+        got: got.to_string(),
+    };
+    for entry in &transcript.entries {
+        let mismatch = |expected: &str, got: &str| TranscriptError::Mismatch {
+            line: entry.line,
+            expected: expected.to_string(),
+            got: got.to_string(),
+        };
+        if let Some(pending) = response {
+            if !matches!(entry.event, Event::Response(_)) {
+                return Err(unanswered(pending));
+            }
+        }
+        game = game.with_elapsed(entry.at);
+        match &entry.event {
+            Event::Input(text) => {
+                inputs += 1;
+                response = Some((entry.line, respond(&mut game, text)));
+            }
+            Event::Response(expected) => match response.take() {
+                Some((_, got)) if got == expected => {}
+                Some((_, got)) => return Err(mismatch(expected, got)),
+                None => return Err(mismatch(expected, &i18n::text("transcript-no-input"))),
+            },
+            Event::Timeout => {
+                game.check_time();
+                if !game.timed_out() {
+                    return Err(mismatch("timeout", outcome_code(game.outcome())));
+                }
+            }
+            Event::Outcome(expected) => {
+                let got = outcome_code(game.outcome());
+                if got != expected {
+                    return Err(mismatch(expected, got));
+                }
+            }
+        }
+    }
+    match response {
+        Some(pending) => Err(unanswered(pending)),
+        None => Ok(inputs),
+    }
+}
+
+// What the engine makes of one line of input, as it's written in a transcript.
+pub fn respond(game: &mut Game, text: &str) -> &'static str {
+    match input::parse_guess(text, game.range()) {
+        Ok(guess) => match game.guess(guess) {
+            Ok(ordering) => answer_code(ordering),
+            Err(err) => batch::guess_error_code(&err),
+        },
+        Err(err) => batch::error_code(&err),
+    }
+}
+
+pub fn answer_code(ordering: Ordering) -> &'static str {
+    match ordering {
+        Ordering::Less => "less",
+        Ordering::Greater => "greater",
+        Ordering::Equal => "equal",
+    }
+}
+
+pub fn outcome_code(outcome: Outcome) -> &'static str {
+    match outcome {
+        Outcome::InProgress => "unfinished",
+        Outcome::Won { .. } => "won",
+        Outcome::Lost { .. } => "lost",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    // The example from the top of this file.
+    const EXAMPLE: &str = "\
+guessing-game transcript 1
+seed 9
+range 1 100
+host fair
+secrets uniform
+max-attempts 7
+time-limit-ms 30000
+1520 input 50
+1520 response greater
+2810 input abc
+2810 response not_a_number
+4077 input 12
+4077 response equal
+4077 outcome won
+";
+
+    fn entry(line: usize, millis: u64, event: Event) -> Entry {
+        Entry {
+            line,
+            at: Duration::from_millis(millis),
+            event,
+        }
+    }
+
+    // The example, with `line` (counting from 1) replaced.
+    fn edited(line: usize, replacement: &str) -> String {
+        let mut lines: Vec<&str> = EXAMPLE.lines().collect();
+        lines[line - 1] = replacement;
+        lines.join("\n")
+    }
+
+    fn mismatch(text: &str) -> (usize, String, String) {
+        match replay(&Transcript::parse(text).unwrap()) {
+            Err(TranscriptError::Mismatch {
+                line,
+                expected,
+                got,
+            }) => (line, expected, got),
+            other => panic!("expected a mismatch, got {other:?}"),
+        }
+    }
+
+    fn format_error(text: &str) -> (usize, String) {
+        match Transcript::parse(text) {
+            Err(TranscriptError::Format { line, message }) => (line, message),
+            other => panic!("expected a format error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn the_example_parses_and_replays() {
+        let transcript = Transcript::parse(EXAMPLE).unwrap();
+        assert_eq!(
+            transcript.header,
+            Header {
+                seed: 9,
+                range: 1..=100,
+                host: Host::Fair,
+                secrets: Secrets::Uniform,
+                max_attempts: Some(7),
+                time_limit: Some(Duration::from_secs(30)),
+            }
+        );
+        assert_eq!(transcript.entries.len(), 7);
+        assert_eq!(
+            transcript.entries[2],
+            entry(10, 2810, Event::Input("abc".to_string()))
+        );
+        assert_eq!(
+            transcript.entries[6],
+            entry(14, 4077, Event::Outcome("won".to_string()))
+        );
+        assert_eq!(replay(&transcript).unwrap(), 3);
+    }
+
+    #[test]
+    fn a_recording_reads_back_the_same() {
+        let path = env::temp_dir().join(format!(
+            "guessing_game-transcript-{}-round-trip",
+            process::id()
+        ));
+        let transcript = Transcript::parse(EXAMPLE).unwrap();
+        let mut recorder = Recorder::create(&path, &transcript.header).unwrap();
+        for entry in &transcript.entries {
+            recorder.record(entry.at, &entry.event).unwrap();
+        }
+        drop(recorder);
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), EXAMPLE);
+        assert_eq!(Transcript::load(&path).unwrap(), transcript);
+        fs::remove_file(&path).unwrap();
+
+        // Without limits, and with the time-up event.
+        let header = Header {
+            max_attempts: None,
+            time_limit: None,
+            ..transcript.header
+        };
+        let text = "guessing-game transcript 1\nseed 9\nrange 1 100\nhost fair\n\
+                    secrets uniform\n31000 timeout\n";
+        let parsed = Transcript::parse(text).unwrap();
+        assert_eq!(parsed.header, header);
+        assert_eq!(parsed.entries, [entry(6, 31_000, Event::Timeout)]);
+    }
+
+    #[test]
+    fn a_different_answer_is_a_mismatch() {
+        assert_eq!(
+            mismatch(&edited(9, "1520 response less")),
+            (9, "less".to_string(), "greater".to_string())
+        );
+        let err =
+            replay(&Transcript::parse(&edited(9, "1520 response less")).unwrap()).unwrap_err();
+        assert_eq!(err.to_string(), "line 9: expected `less`, got `greater`");
+        assert_eq!(
+            i18n::in_language("nl", || err.to_string()),
+            "regel 9: verwacht `less`, maar kreeg `greater`"
+        );
+        assert_eq!(
+            mismatch(&edited(14, "4077 outcome lost")),
+            (14, "lost".to_string(), "won".to_string())
+        );
+        // Another seed draws another secret.
+        assert_eq!(
+            mismatch(&edited(2, "seed 10")).0,
+            9,
+            "the first answer already differs"
+        );
+        // The time limit was never reached.
+        assert_eq!(
+            mismatch(&edited(14, "4077 timeout")),
+            (14, "timeout".to_string(), "won".to_string())
+        );
+    }
+
+    #[test]
+    fn inputs_and_responses_come_in_pairs() {
+        // A response without an input.
+        assert_eq!(
+            mismatch(&edited(8, "1520 response greater")),
+            (8, "greater".to_string(), "no input".to_string())
+        );
+        // An input without a response, followed by another input.
+        assert_eq!(
+            mismatch(&edited(9, "1520 input 60")),
+            (8, "no response".to_string(), "greater".to_string())
+        );
+        // The last input without a response.
+        let unfinished: String = EXAMPLE
+            .lines()
+            .take(12)
+            .map(|line| line.to_string() + "\n")
+            .collect();
+        assert_eq!(
+            mismatch(&unfinished),
+            (12, "no response".to_string(), "equal".to_string())
+        );
+    }
+
+    #[test]
+    fn only_transcripts_are_read() {
+        assert_eq!(format_error("").0, 1);
+        assert_eq!(
+            format_error(&edited(1, "guessing-game transcript 2")),
+            (1, "unknown version 2".to_string())
+        );
+        assert_eq!(
+            format_error(&edited(3, "range 100 1")),
+            (3, "invalid range `100 1`".to_string())
+        );
+        assert_eq!(
+            format_error(&edited(8, "1520 shout 50")),
+            (8, "unknown event `shout`".to_string())
+        );
+        assert_eq!(
+            format_error(&(EXAMPLE.to_string() + "seed 10\n")),
+            (15, "`seed` after the first event".to_string())
+        );
+        assert_eq!(format_error(&edited(2, "")), (1, "no `seed`".to_string()));
+        let err = i18n::in_language("nl", || {
+            let err = Transcript::parse(&edited(8, "1520 shout 50")).unwrap_err();
+            err.to_string()
+        });
+        assert_eq!(
+            err,
+            "geen geldig transcript, regel 8: onbekende gebeurtenis `shout`"
+        );
+    }
+}
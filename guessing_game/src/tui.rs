@@ -0,0 +1,474 @@
+use std::cmp::Ordering;
+use std::io;
+use std::time::Duration;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::bot::Bounds;
+use crate::game::{Game, Outcome};
+use crate::{i18n, input};
+
+// ========== Terminal UI ==========
+/*
+A full-screen front-end for the same `Game` engine, using ratatui (with its crossterm backend, which
+only needs a terminal that understands ANSI escape codes, so it runs fine over ssh or in a container).
+
+    ┌ Guess the number! ───────────────────────────────────────────┐
+    │normal: 1 to 100, fair host, seed 42                          │
+    └──────────────────────────────────────────────────────────────┘
+    ┌ Still possible: 51 to 74 ────────────────────────────────────┐
+    │░░░░░░░░░░░░░░░░░░░░░░░░░░░░░░░███████████████░░░░░░░░░░░░░░░░│
+    └──────────────────────────────────────────────────────────────┘
+    ┌ Guesses ─────────────────────┐┌ Game ────────────────────────┐
+    │  2. 75           too big     ││Attempts: 2 of 7              │
+    │  1. 50           too small   ││Time: 0.0s                    │
+    └──────────────────────────────┘└──────────────────────────────┘
+    ┌ Your guess ──────────────────────────────────────────────────┐
+    │6_                                                            │
+    │75 is too big!                                                │
+    └ Enter: guess  Backspace: delete  Esc: quit ──────────────────┘
+
+Everything is done with the keyboard: type digits (and `-`), Backspace to correct, Enter to guess,
+Esc to quit. The terminal is in raw mode, so Ctrl-C arrives as a key press instead of a signal.
+
+`draw` only reads the `App`, so a frame can be rendered into any ratatui backend, not just a terminal.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exit {
+    // The game was won or lost, and the player pressed a key to leave the final screen.
+    Finished,
+    // Esc before the game was finished.
+    Quit,
+    // Ctrl-C.
+    Interrupted,
+}
+
+#[derive(Debug, Clone)]
+pub struct App {
+    game: Game,
+    subtitle: String,
+    input: String,
+    message: String,
+    exit: Option<Exit>,
+}
+
+impl App {
+    // `subtitle` is shown under the title, e.g. the difficulty and the seed.
+    pub fn new(game: Game, subtitle: &str) -> App {
+        App {
+            game,
+            subtitle: subtitle.to_string(),
+            input: String::new(),
+            message: i18n::text("tui-start"),
+            exit: None,
+        }
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn into_game(self) -> Game {
+        self.game
+    }
+
+    pub fn exit(&self) -> Option<Exit> {
+        self.exit
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if key.kind != KeyEventKind::Press {
+            return;
+        }
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.exit = Some(Exit::Interrupted);
+            return;
+        }
+        // On the final screen any key leaves.
+        if self.game.is_finished() {
+            self.exit = Some(Exit::Finished);
+            return;
+        }
+
+        match key.code {
+            KeyCode::Esc => self.exit = Some(Exit::Quit),
+            KeyCode::Char(c) if c.is_ascii_digit() || (c == '-' && self.input.is_empty()) => {
+                self.input.push(c)
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Enter => self.submit(),
+            _ => {}
+        }
+    }
+
+    // Same rules as the line based game: bad input gets a message and isn't counted.
+    fn submit(&mut self) {
+        let line = std::mem::take(&mut self.input);
+        let guess = match input::parse_guess(&line, self.game.range()) {
+            Ok(guess) => guess,
+            Err(err) => {
+                self.message = err.to_string();
+                return;
+            }
+        };
+
+        self.message = match self.game.guess(guess) {
+            Ok(Ordering::Less) => i18n::message("tui-too-small", &[("guess", &guess)]),
+            Ok(Ordering::Greater) => i18n::message("tui-too-big", &[("guess", &guess)]),
+            Ok(Ordering::Equal) => i18n::message("tui-right", &[("guess", &guess)]),
+            Err(err) => err.to_string(),
+        };
+
+        match self.game.outcome() {
+            Outcome::InProgress => {}
+            Outcome::Won { attempts } => {
+                self.message = i18n::message(
+                    "tui-won",
+                    &[
+                        ("guess", &guess),
+                        ("attempts", &attempts),
+                        ("score", &self.game.score().unwrap_or(0)),
+                    ],
+                )
+            }
+            Outcome::Lost { .. } if self.game.timed_out() => self.time_up(),
+            Outcome::Lost { .. } => {
+                self.message = i18n::message("tui-lost", &[("secret", &self.game.secret())])
+            }
+        }
+    }
+
+    // Called a few times a second, so a time limit can end the game while the player is typing.
+    pub fn tick(&mut self) {
+        if self.game.is_finished() {
+            return;
+        }
+        self.game.check_time();
+        if self.game.timed_out() {
+            self.time_up();
+        }
+    }
+
+    fn time_up(&mut self) {
+        self.message = i18n::message("tui-time-up", &[("secret", &self.game.secret())]);
+    }
+
+    // What the player knows so far: the numbers that agree with every answer.
+    fn bounds(&self) -> Bounds {
+        let mut bounds = Bounds::new(self.game.range());
+        for &(guess, ordering) in self.game.history() {
+            bounds.update(guess, ordering);
+        }
+        bounds
+    }
+}
+
+// Takes over the terminal until the player leaves, and puts it back the way it was afterwards.
+pub fn run(mut app: App) -> io::Result<App> {
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app);
+    ratatui::restore();
+    result.map(|_| app)
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App) -> io::Result<()> {
+    while app.exit.is_none() {
+        terminal.draw(|frame| draw(frame, app))?;
+        // Wake up a few times a second even without a key press, so the timer keeps running.
+        if event::poll(Duration::from_millis(200))? {
+            if let Event::Key(key) = event::read()? {
+                app.handle_key(key);
+            }
+        }
+        app.tick();
+    }
+    Ok(())
+}
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [title, number_line, middle, input_area] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Length(3),
+        Constraint::Min(4),
+        Constraint::Length(4),
+    ])
+    .areas(frame.area());
+    let [history, stats] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(middle);
+
+    frame.render_widget(
+        Paragraph::new(app.subtitle.as_str())
+            .block(Block::bordered().title(format!(" {} ", i18n::text("tui-title")))),
+        title,
+    );
+
+    let bounds = app.bounds();
+    let width = number_line.width.saturating_sub(2) as usize;
+    frame.render_widget(
+        Paragraph::new(number_line_cells(app.game.range(), &bounds, width))
+            .style(Style::default().fg(Color::Green))
+            .block(Block::bordered().title(format!(
+                " {} ",
+                i18n::message(
+                    "tui-still-possible",
+                    &[("low", &bounds.low), ("high", &bounds.high)]
+                )
+            ))),
+        number_line,
+    );
+
+    // Newest guess at the top, so the latest answer is always in view.
+    let items: Vec<ListItem> = app
+        .game
+        .history()
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, (guess, ordering))| {
+            let answer = match ordering {
+                Ordering::Less => i18n::text("tui-answer-too-small").yellow(),
+                Ordering::Greater => i18n::text("tui-answer-too-big").red(),
+                Ordering::Equal => i18n::text("tui-answer-correct").green(),
+            };
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{:>3}. {guess:<12} ", i + 1)),
+                answer,
+            ]))
+        })
+        .collect();
+    frame.render_widget(
+        List::new(items).block(Block::bordered().title(format!(" {} ", i18n::text("tui-guesses")))),
+        history,
+    );
+
+    let attempts = match app.game.max_attempts() {
+        Some(max) => i18n::message(
+            "tui-attempts-of",
+            &[("attempts", &app.game.attempts()), ("max", &max)],
+        ),
+        None => i18n::message("tui-attempts", &[("attempts", &app.game.attempts())]),
+    };
+    frame.render_widget(
+        Paragraph::new(vec![
+            Line::from(attempts),
+            Line::from(i18n::message(
+                "tui-time",
+                &[(
+                    "seconds",
+                    &format!("{:.1}", app.game.elapsed().as_secs_f64()),
+                )],
+            )),
+            Line::from(match app.game.time_left() {
+                Some(left) => i18n::message(
+                    "tui-time-left",
+                    &[("seconds", &format!("{:.1}", left.as_secs_f64()))],
+                ),
+                None => String::new(),
+            }),
+        ])
+        .block(Block::bordered().title(format!(" {} ", i18n::text("tui-game")))),
+        stats,
+    );
+
+    frame.render_widget(
+        Paragraph::new(vec![
+            Line::from(format!("{}_", app.input)).bold(),
+            Line::from(app.message.as_str()).dim(),
+        ])
+        .block(
+            Block::bordered()
+                .title(format!(" {} ", i18n::text("tui-your-guess")))
+                .title_bottom(format!(" {} ", i18n::text("tui-keys"))),
+        ),
+        input_area,
+    );
+}
+
+/*
+One cell per slice of the range: a full block where the secret can still be, a light shade where it can't.
+Every cell stands for (range size / width) numbers; a cell is full as soon as one of them is still possible.
+The maths is done in i128, so the full i64 range fits.
+ */
+fn number_line_cells(
+    range: &std::ops::RangeInclusive<i64>,
+    bounds: &Bounds,
+    width: usize,
+) -> String {
+    let start = *range.start() as i128;
+    let size = *range.end() as i128 - start + 1;
+    let width = width.max(1) as i128;
+
+    (0..width)
+        .map(|cell| {
+            let first = start + cell * size / width;
+            let last = (start + (cell + 1) * size / width - 1).max(first);
+            if last >= bounds.low as i128 && first <= bounds.high as i128 {
+                '█'
+            } else {
+                '░'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn render(app: &App) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(64, 15)).unwrap();
+        terminal.draw(|frame| draw(frame, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn press(app: &mut App, keys: &str) {
+        for key in keys.chars() {
+            app.handle_key(KeyEvent::from(KeyCode::Char(key)));
+        }
+    }
+
+    fn enter(app: &mut App) {
+        app.handle_key(KeyEvent::from(KeyCode::Enter));
+    }
+
+    // 42 of 60 seconds gone, so the clock shows the same thing on every run.
+    fn app() -> App {
+        let game = Game::new(1..=100, 60)
+            .with_max_attempts(Some(7))
+            .with_time_limit(Some(Duration::from_secs(60)))
+            .with_stopped_clock(Duration::from_secs(42));
+        App::new(game, "normal: 1 to 100, seed 42")
+    }
+
+    #[test]
+    fn the_first_frame_shows_the_whole_range() {
+        assert_eq!(
+            render(&app()),
+            [
+                "┌ Guess the number! ───────────────────────────────────────────┐",
+                "│normal: 1 to 100, seed 42                                     │",
+                "└──────────────────────────────────────────────────────────────┘",
+                "┌ Still possible: 1 to 100 ────────────────────────────────────┐",
+                "│██████████████████████████████████████████████████████████████│",
+                "└──────────────────────────────────────────────────────────────┘",
+                "┌ Guesses ─────────────────────┐┌ Game ────────────────────────┐",
+                "│                              ││Attempts: 0 of 7              │",
+                "│                              ││Time: 42.0s                   │",
+                "│                              ││Time left: 18.0s              │",
+                "└──────────────────────────────┘└──────────────────────────────┘",
+                "┌ Your guess ──────────────────────────────────────────────────┐",
+                "│_                                                             │",
+                "│Type a number and press Enter.                                │",
+                "└ Enter: guess  Backspace: delete  Esc: quit ──────────────────┘",
+            ]
+        );
+    }
+
+    #[test]
+    fn guesses_narrow_the_number_line_and_fill_the_history() {
+        let mut app = app();
+        press(&mut app, "50");
+        enter(&mut app);
+        press(&mut app, "75");
+        enter(&mut app);
+        press(&mut app, "6");
+        assert_eq!(
+            render(&app),
+            [
+                "┌ Guess the number! ───────────────────────────────────────────┐",
+                "│normal: 1 to 100, seed 42                                     │",
+                "└──────────────────────────────────────────────────────────────┘",
+                "┌ Still possible: 51 to 74 ────────────────────────────────────┐",
+                "│░░░░░░░░░░░░░░░░░░░░░░░░░░░░░░░███████████████░░░░░░░░░░░░░░░░│",
+                "└──────────────────────────────────────────────────────────────┘",
+                "┌ Guesses ─────────────────────┐┌ Game ────────────────────────┐",
+                "│  2. 75           too big     ││Attempts: 2 of 7              │",
+                "│  1. 50           too small   ││Time: 42.0s                   │",
+                "│                              ││Time left: 18.0s              │",
+                "└──────────────────────────────┘└──────────────────────────────┘",
+                "┌ Your guess ──────────────────────────────────────────────────┐",
+                "│6_                                                            │",
+                "│75 is too big!                                                │",
+                "└ Enter: guess  Backspace: delete  Esc: quit ──────────────────┘",
+            ]
+        );
+    }
+
+    #[test]
+    fn the_game_panel_counts_without_limits() {
+        let game = Game::new(1..=100, 60).with_stopped_clock(Duration::from_secs(3));
+        let app = App::new(game, "");
+        let lines = render(&app);
+        assert_eq!(
+            &lines[7..10],
+            [
+                "│                              ││Attempts: 0                   │",
+                "│                              ││Time: 3.0s                    │",
+                "│                              ││                              │",
+            ]
+        );
+    }
+
+    #[test]
+    fn enter_guesses_what_was_typed() {
+        let mut app = app();
+        press(&mut app, "60");
+        enter(&mut app);
+        assert_eq!(app.game().outcome(), Outcome::Won { attempts: 1 });
+        assert_eq!(app.exit(), None);
+        // On the final screen any key leaves.
+        press(&mut app, "1");
+        assert_eq!(app.exit(), Some(Exit::Finished));
+    }
+
+    #[test]
+    fn enter_without_a_number_is_not_counted() {
+        let mut app = app();
+        enter(&mut app);
+        press(&mut app, "-");
+        press(&mut app, "-");
+        enter(&mut app);
+        assert_eq!(app.game().attempts(), 0);
+        assert_eq!(app.exit(), None);
+    }
+
+    #[test]
+    fn esc_quits() {
+        let mut app = app();
+        press(&mut app, "5");
+        app.handle_key(KeyEvent::from(KeyCode::Esc));
+        assert_eq!(app.exit(), Some(Exit::Quit));
+        assert_eq!(app.game().attempts(), 0);
+    }
+
+    #[test]
+    fn ctrl_c_interrupts() {
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        let mut playing = app();
+        playing.handle_key(ctrl_c);
+        assert_eq!(playing.exit(), Some(Exit::Interrupted));
+
+        // Also on the final screen.
+        let mut finished = app();
+        press(&mut finished, "60");
+        enter(&mut finished);
+        finished.handle_key(ctrl_c);
+        assert_eq!(finished.exit(), Some(Exit::Interrupted));
+    }
+}
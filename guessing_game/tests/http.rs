@@ -0,0 +1,65 @@
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::thread;
+
+use serde_json::{json, Value};
+
+use guessing_game::http::{self, Api, DEFAULT_SESSION_TIMEOUT};
+
+fn start_server() -> SocketAddr {
+    let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+    let addr = server.server_addr().to_ip().unwrap();
+    thread::spawn(move || http::serve(server, Api::new(1..=100, DEFAULT_SESSION_TIMEOUT)));
+    addr
+}
+
+// One request on a connection of its own; returns the status, the content type and the body.
+fn request(addr: SocketAddr, method: &str, path: &str, body: &str) -> (u16, String, Value) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "{method} {path} HTTP/1.1\r\nHost: {addr}\r\nConnection: close\r\n\
+         Content-Length: {}\r\n\r\n{body}",
+        body.len()
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+    let content_type = head
+        .lines()
+        .find_map(|line| line.strip_prefix("Content-Type: "))
+        .unwrap_or("")
+        .to_string();
+    (status, content_type, serde_json::from_str(body).unwrap())
+}
+
+#[test]
+fn a_game_over_http() {
+    let addr = start_server();
+
+    let (status, content_type, game) = request(addr, "POST", "/games", r#"{"min": 7, "max": 7}"#);
+    assert_eq!(status, 201);
+    assert_eq!(content_type, "application/json");
+    assert_eq!(game["status"], "in_progress");
+    let id = game["id"].as_u64().unwrap();
+
+    let guesses = format!("/games/{id}/guesses");
+    let (status, _, error) = request(addr, "POST", &guesses, r#"{"guess": 8}"#);
+    assert_eq!(status, 422);
+    assert!(error["error"].is_string());
+
+    let (status, _, game) = request(addr, "POST", &guesses, r#"{"guess": 7}"#);
+    assert_eq!(status, 200);
+    assert_eq!(game["result"], "equal");
+    assert_eq!(game["status"], "won");
+
+    let (status, _, game) = request(addr, "GET", &format!("/games/{id}"), "");
+    assert_eq!(status, 200);
+    assert_eq!(game["history"], json!([{ "guess": 7, "result": "equal" }]));
+
+    let (status, _, _) = request(addr, "GET", "/games/999", "");
+    assert_eq!(status, 404);
+}
@@ -0,0 +1,132 @@
+use std::net::SocketAddr;
+use std::thread;
+
+use rand::Rng;
+
+use guessing_game::multiplayer::{Client, ClientMessage, Server, ServerMessage};
+use guessing_game::seed;
+
+const SEED: u64 = 7;
+
+// A server on a free port, running on a thread of its own, and the secret of its first round.
+fn start_server() -> (SocketAddr, i64) {
+    let server = Server::bind("127.0.0.1:0", 1..=100, SEED).unwrap();
+    let addr = server.local_addr().unwrap();
+    thread::spawn(move || server.run());
+    // The first round draws its secret first thing, the same way.
+    let secret = seed::rng_from_seed(SEED).gen_range(1..=100);
+    (addr, secret)
+}
+
+fn receive(client: &mut Client) -> ServerMessage {
+    client
+        .receive()
+        .unwrap()
+        .expect("the server closed the connection")
+}
+
+// Connects as the `id`th player during `round` and picks a name; returns once the client saw its
+// own JOINED, under the default name and then under its own.
+fn join(addr: SocketAddr, name: &str, id: u32, round: u32) -> Client {
+    let mut client = Client::connect(addr).unwrap();
+    assert_eq!(
+        receive(&mut client),
+        ServerMessage::Welcome {
+            round,
+            min: 1,
+            max: 100
+        }
+    );
+    assert_eq!(
+        receive(&mut client),
+        ServerMessage::Joined(format!("player{id}"))
+    );
+    client.send(&ClientMessage::Name(name.to_string())).unwrap();
+    assert_eq!(
+        receive(&mut client),
+        ServerMessage::Joined(name.to_string())
+    );
+    client
+}
+
+fn guess(client: &mut Client, guess: i64) {
+    client
+        .send(&ClientMessage::Guess(guess.to_string()))
+        .unwrap();
+}
+
+#[test]
+fn players_race_for_the_same_secret() {
+    let (addr, secret) = start_server();
+    assert!(
+        (2..=99).contains(&secret),
+        "pick a seed without a secret on the edge"
+    );
+
+    let mut ada = join(addr, "ada", 1, 1);
+    let mut bob = join(addr, "bob", 2, 1);
+    assert_eq!(
+        receive(&mut ada),
+        ServerMessage::Joined("player2".to_string())
+    );
+    assert_eq!(receive(&mut ada), ServerMessage::Joined("bob".to_string()));
+
+    // Answers only go to the player who guessed.
+    guess(&mut ada, secret - 1);
+    assert_eq!(receive(&mut ada), ServerMessage::TooSmall(secret - 1));
+    guess(&mut ada, secret + 1);
+    assert_eq!(receive(&mut ada), ServerMessage::TooBig(secret + 1));
+    guess(&mut ada, 1000);
+    assert!(matches!(receive(&mut ada), ServerMessage::Error(_)));
+
+    // The winner is announced to everyone, and the next round starts.
+    guess(&mut bob, secret);
+    assert_eq!(receive(&mut bob), ServerMessage::Correct(secret));
+    let winner = ServerMessage::Winner {
+        name: "bob".to_string(),
+        attempts: 1,
+    };
+    let round = ServerMessage::Round {
+        round: 2,
+        min: 1,
+        max: 100,
+    };
+    for client in [&mut bob, &mut ada] {
+        assert_eq!(receive(client), winner);
+        assert_eq!(receive(client), round);
+    }
+
+    // Leaving is announced too, whether with QUIT or by just going away.
+    bob.send(&ClientMessage::Quit).unwrap();
+    assert_eq!(receive(&mut ada), ServerMessage::Left("bob".to_string()));
+    let cy = join(addr, "cy", 3, 2);
+    assert_eq!(
+        receive(&mut ada),
+        ServerMessage::Joined("player3".to_string())
+    );
+    assert_eq!(receive(&mut ada), ServerMessage::Joined("cy".to_string()));
+    drop(cy);
+    assert_eq!(receive(&mut ada), ServerMessage::Left("cy".to_string()));
+}
+
+#[test]
+fn a_broken_connection_still_leaves() {
+    use std::io::Write;
+    use std::net::TcpStream;
+
+    let (addr, _) = start_server();
+    let mut ada = join(addr, "ada", 1, 1);
+
+    // Not UTF-8: the server can't read this line, and drops the connection. Every player that
+    // leaves was announced joining first, even without a name.
+    let mut raw = TcpStream::connect(addr).unwrap();
+    assert_eq!(
+        receive(&mut ada),
+        ServerMessage::Joined("player2".to_string())
+    );
+    raw.write_all(b"NAME \xff\xfe\n").unwrap();
+    assert_eq!(
+        receive(&mut ada),
+        ServerMessage::Left("player2".to_string())
+    );
+}
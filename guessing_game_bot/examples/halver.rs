@@ -0,0 +1,24 @@
+// ========== Halver ==========
+/*
+The bot from the top of src/lib.rs: always the middle of what's left, so never more than log2 of
+the range in guesses. Build it with
+
+    cargo build --release --example halver --target wasm32-unknown-unknown
+
+and play it with `--bot target/wasm32-unknown-unknown/release/examples/halver.wasm`. A bot in a
+crate of its own needs `crate-type = ["cdylib"]` in its `[lib]` section; for this example that's
+in the `[[example]]` section of Cargo.toml.
+ */
+use guessing_game_bot::{export_bot, Bot};
+
+#[derive(Default)]
+struct Halver;
+
+impl Bot for Halver {
+    fn next_guess(&mut self, low: i64, high: i64) -> i64 {
+        // In i128, so the full i64 range doesn't overflow.
+        ((low as i128 + high as i128) / 2) as i64
+    }
+}
+
+export_bot!(Halver);
@@ -0,0 +1,103 @@
+// ========== Guessing game bots ==========
+/*
+Everything a bot needs to play in a `guessing_game tournament`, written in Rust and compiled to
+WebAssembly. The game's side of the interface is in guessing_game/src/plugin.rs.
+
+A bot is a type with `Default` and the `Bot` trait, exported with `export_bot!`:
+
+    use guessing_game_bot::{export_bot, Bot};
+
+    #[derive(Default)]
+    struct Halver;
+
+    impl Bot for Halver {
+        fn next_guess(&mut self, low: i64, high: i64) -> i64 {
+            // In i128, so the full i64 range doesn't overflow.
+            ((low as i128 + high as i128) / 2) as i64
+        }
+    }
+
+    export_bot!(Halver);
+
+Its crate needs `crate-type = ["cdylib"]` in the `[lib]` section of Cargo.toml, and is built with
+
+    cargo build --release --target wasm32-unknown-unknown
+
+which leaves `halver.wasm` in target/wasm32-unknown-unknown/release, ready for `--bot`.
+examples/halver.rs is this bot, with the build command for an example.
+
+The bot runs in a sandbox: no files, no network, no clock, a limited amount of fuel for every
+guess and a limited amount of memory. Randomness comes from `random`, which draws from the game's
+seeded generator, so a tournament with the same seed plays out the same way every time.
+ */
+
+// The version of the interface between the game and the bot. It only changes when a bot built
+// against an older version would no longer work.
+pub const ABI_VERSION: i32 = 1;
+
+pub trait Bot: Default {
+    // A new game starts, with a secret from `low` to `high`. The bot is reset to its `Default`
+    // right before this, so there's only something to do here for bots that look at the range.
+    fn new_game(&mut self, _low: i64, _high: i64) {}
+
+    // The next guess: the secret is somewhere from `low` to `high` (both included), as far as the
+    // answers so far go.
+    fn next_guess(&mut self, low: i64, high: i64) -> i64;
+}
+
+// A random number from `low` to `high`, both included.
+pub fn random(low: i64, high: i64) -> i64 {
+    host::random(low, high)
+}
+
+#[cfg(target_arch = "wasm32")]
+mod host {
+    #[link(wasm_import_module = "guessing_game")]
+    extern "C" {
+        #[link_name = "random"]
+        fn host_random(low: i64, high: i64) -> i64;
+    }
+
+    pub fn random(low: i64, high: i64) -> i64 {
+        // The game checks the arguments, and stops the bot when they're wrong.
+        unsafe { host_random(low, high) }
+    }
+}
+
+// Outside WebAssembly there's no game to ask.
+#[cfg(not(target_arch = "wasm32"))]
+mod host {
+    pub fn random(_low: i64, _high: i64) -> i64 {
+        panic!("`random` only works inside the game, build the bot for wasm32-unknown-unknown")
+    }
+}
+
+// The exports the game looks for, see the top of this file. Use it once, in the crate root.
+#[macro_export]
+macro_rules! export_bot {
+    ($bot:ty) => {
+        thread_local! {
+            static BOT: ::std::cell::RefCell<$bot> =
+                ::std::cell::RefCell::new(<$bot as ::std::default::Default>::default());
+        }
+
+        #[unsafe(no_mangle)]
+        pub extern "C" fn abi_version() -> i32 {
+            $crate::ABI_VERSION
+        }
+
+        #[unsafe(no_mangle)]
+        pub extern "C" fn new_game(low: i64, high: i64) {
+            BOT.with(|bot| {
+                let mut bot = bot.borrow_mut();
+                *bot = <$bot as ::std::default::Default>::default();
+                $crate::Bot::new_game(&mut *bot, low, high);
+            })
+        }
+
+        #[unsafe(no_mangle)]
+        pub extern "C" fn next_guess(low: i64, high: i64) -> i64 {
+            BOT.with(|bot| $crate::Bot::next_guess(&mut *bot.borrow_mut(), low, high))
+        }
+    };
+}